@@ -0,0 +1,234 @@
+use super::{
+    credentials::CredentialsProvider,
+    rest_client::{Balance, OrderResponse, VenueClient},
+    utils::get_http_client,
+    venues::VenueRegistry,
+    ApiFiny, Error, RateLimiter, RestClient, Result, Venue,
+};
+use futures::future::join_all;
+use std::{collections::BTreeMap, future::Future};
+
+// the outcome of a fanned out call on one account and venue
+#[derive(Debug)]
+pub struct AccountResult<T> {
+    pub account: String,
+    pub venue: String,
+    pub result: Result<T>,
+}
+
+struct Entry {
+    client: RestClient,
+    // venue sub accounts are looked up on
+    home: Venue,
+    // venues with a sub account
+    venues: Vec<Venue>,
+}
+
+// Named Apifiny accounts, with clients for every venue sub account sharing
+// one connection pool, rate limiter and venue registry.
+pub struct AccountRegistry {
+    http: reqwest::Client,
+    limiter: RateLimiter,
+    venues: VenueRegistry,
+    accounts: BTreeMap<String, Entry>,
+}
+
+impl AccountRegistry {
+    pub fn new() -> Result<AccountRegistry> {
+        Ok(AccountRegistry {
+            http: get_http_client()?,
+            limiter: RateLimiter::default(),
+            venues: VenueRegistry::default(),
+            accounts: BTreeMap::new(),
+        })
+    }
+
+    // also applies to accounts already added
+    pub fn with_rate_limiter(mut self, limiter: RateLimiter) -> AccountRegistry {
+        for entry in self.accounts.values_mut() {
+            entry.client = entry.client.clone().with_rate_limiter(limiter.clone());
+        }
+        self.limiter = limiter;
+        self
+    }
+
+    // also applies to accounts already added
+    pub fn with_venue_registry(mut self, venues: VenueRegistry) -> AccountRegistry {
+        for entry in self.accounts.values_mut() {
+            entry.client = entry.client.clone().with_venue_registry(venues.clone());
+        }
+        self.venues = venues;
+        self
+    }
+
+    // Add or replace the account `name`, `home` is the venue its sub
+    // accounts are looked up on and its only venue until `discover`.
+    pub fn add(&mut self, name: &str, conf: ApiFiny, home: Venue) -> Result<()> {
+        let client = RestClient::new(conf)?
+            .with_http_client(self.http.clone())
+            .with_rate_limiter(self.limiter.clone())
+            .with_venue_registry(self.venues.clone());
+        self.accounts.insert(
+            name.to_string(),
+            Entry {
+                client,
+                home,
+                venues: vec![home],
+            },
+        );
+        Ok(())
+    }
+
+    pub fn add_from(
+        &mut self,
+        name: &str,
+        provider: &dyn CredentialsProvider,
+        home: Venue,
+    ) -> Result<()> {
+        self.add(name, provider.credentials()?, home)
+    }
+
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.accounts.remove(name).is_some()
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.accounts.keys().map(String::as_str)
+    }
+
+    pub fn venues(&self, name: &str) -> Option<&[Venue]> {
+        self.accounts.get(name).map(|e| e.venues.as_slice())
+    }
+
+    // client of account `name` on `venue`
+    pub fn client(&self, name: &str, venue: Venue) -> Option<VenueClient<'_>> {
+        self.accounts.get(name).map(|e| e.client.venue(venue))
+    }
+
+    // every account and venue pair
+    pub fn clients(&self) -> Vec<(String, VenueClient<'_>)> {
+        self.accounts
+            .iter()
+            .flat_map(|(name, e)| {
+                e.venues
+                    .iter()
+                    .map(move |v| (name.clone(), e.client.venue(*v)))
+            })
+            .collect()
+    }
+
+    // Set each account's venues from its `sub_account_info`. Accounts that
+    // fail keep their venues and are returned with the error.
+    pub async fn discover(&mut self) -> Vec<(String, Error)> {
+        let found = join_all(self.accounts.iter().map(|(name, e)| async move {
+            let venues = async {
+                let account = e
+                    .client
+                    .venue(e.home)
+                    .query_account_info()
+                    .await?
+                    .into_result()?;
+                let mut venues = vec![];
+                for sub in account.sub_account_info() {
+                    match e.client.venues().get(sub.venue()) {
+                        Ok(v) if !venues.contains(&v) => venues.push(v),
+                        Ok(_) => {}
+                        Err(err) => tracing::warn!("{}: {}", name, err),
+                    }
+                }
+                Ok::<_, Error>(venues)
+            };
+            (name.clone(), venues.await)
+        }))
+        .await;
+
+        let mut failures = vec![];
+        for (name, result) in found {
+            match result {
+                Ok(venues) if !venues.is_empty() => {
+                    if let Some(e) = self.accounts.get_mut(&name) {
+                        e.venues = venues;
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => failures.push((name, e)),
+            }
+        }
+        failures
+    }
+
+    // run `f` on every account and venue concurrently
+    pub async fn fan_out<'a, T, F, Fut>(&'a self, f: F) -> Vec<AccountResult<T>>
+    where
+        F: Fn(VenueClient<'a>) -> Fut,
+        Fut: Future<Output = Result<T>> + 'a,
+    {
+        join_all(self.clients().into_iter().map(|(account, client)| {
+            let venue = client.venue().name.to_string();
+            let fut = f(client);
+            async move {
+                AccountResult {
+                    account,
+                    venue,
+                    result: fut.await,
+                }
+            }
+        }))
+        .await
+    }
+
+    pub async fn balances(&self) -> Vec<AccountResult<Vec<Balance>>> {
+        self.fan_out(|client| async move { client.list_balance().await?.into_result() })
+            .await
+    }
+
+    pub async fn open_orders(&self) -> Vec<AccountResult<Vec<OrderResponse>>> {
+        self.fan_out(|client| async move { client.list_open_order().await })
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{venues::VenueUrls, BINANCE, HUOBI};
+
+    #[test]
+    fn test_clients() {
+        let mut registry = AccountRegistry::new().unwrap();
+        registry
+            .add("desk", ApiFiny::new("key-1", "secret-1", "STA-1"), BINANCE)
+            .unwrap();
+        registry
+            .add("hedge", ApiFiny::new("key-2", "secret-2", "STA-2"), HUOBI)
+            .unwrap();
+        registry
+            .accounts
+            .get_mut("desk")
+            .unwrap()
+            .venues
+            .push(HUOBI);
+
+        let clients: Vec<_> = registry
+            .clients()
+            .into_iter()
+            .map(|(name, c)| (name, c.client().account_id().to_string(), c.venue().name))
+            .collect();
+        assert_eq!(
+            clients,
+            vec![
+                ("desk".to_string(), "STA-1".to_string(), "BINANCE"),
+                ("desk".to_string(), "STA-1".to_string(), "HUOBI"),
+                ("hedge".to_string(), "STA-2".to_string(), "HUOBI"),
+            ]
+        );
+
+        // added accounts pick up registries set later
+        let registry = registry.with_venue_registry(VenueRegistry::new(VenueUrls {
+            rest: "https://{code}.test".to_string(),
+            ..Default::default()
+        }));
+        let client = registry.client("hedge", HUOBI).unwrap();
+        assert_eq!(client.venue().rest, "https://hb.test");
+    }
+}
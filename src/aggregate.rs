@@ -0,0 +1,215 @@
+use super::{
+    rest_client::{OrderBook, PriceSizePair, Ticker},
+    Error, RestClient, Result,
+};
+use futures::future::join_all;
+
+// one venue's view of a symbol
+#[derive(Debug)]
+pub struct VenueQuote {
+    pub venue: String,
+    pub ticker: Ticker,
+    // top of book, none if the book is empty or could not be fetched
+    pub bid: Option<PriceSizePair>,
+    pub ask: Option<PriceSizePair>,
+}
+
+// per venue tickers of one symbol, merged
+#[derive(Debug)]
+pub struct TickerTable {
+    pub symbol: String,
+    pub quotes: Vec<VenueQuote>,
+    // venues whose ticker or order book request failed, a venue whose book
+    // failed is still quoted, without bid and ask
+    pub failures: Vec<(String, Error)>,
+}
+
+impl TickerTable {
+    // highest bid across venues, with its venue
+    pub fn best_bid(&self) -> Option<(&str, &PriceSizePair)> {
+        self.quotes
+            .iter()
+            .filter_map(|q| q.bid.as_ref().map(|bid| (q.venue.as_str(), bid)))
+            .max_by(|a, b| a.1.price().total_cmp(&b.1.price()))
+    }
+
+    // lowest ask across venues, with its venue
+    pub fn best_ask(&self) -> Option<(&str, &PriceSizePair)> {
+        self.quotes
+            .iter()
+            .filter_map(|q| q.ask.as_ref().map(|ask| (q.venue.as_str(), ask)))
+            .min_by(|a, b| a.1.price().total_cmp(&b.1.price()))
+    }
+
+    // last prices weighted by each venue's volume
+    pub fn volume_weighted_price(&self) -> Option<f64> {
+        let (notional, vol) = self.quotes.iter().fold((0.0, 0.0), |(notional, vol), q| {
+            (
+                notional + q.ticker.close() * q.ticker.vol(),
+                vol + q.ticker.vol(),
+            )
+        });
+        if vol > 0.0 {
+            Some(notional / vol)
+        } else {
+            None
+        }
+    }
+
+    pub fn total_volume(&self) -> f64 {
+        self.quotes.iter().map(|q| q.ticker.vol()).sum()
+    }
+}
+
+impl RestClient {
+    /// Ticker of `symbol` on every enabled venue of the client's registry,
+    /// see `ticker_venues` for how long it takes.
+    pub async fn ticker_all_venues(&self, symbol: &str) -> TickerTable {
+        let venues: Vec<&str> = self.venues().enabled().iter().map(|v| v.name).collect();
+        self.ticker_venues(symbol, &venues).await
+    }
+
+    // like `ticker_all_venues`, after refreshing the venues from
+    // `list_venue_info`
+    pub async fn ticker_listed_venues(&self, symbol: &str) -> Result<TickerTable> {
+        self.refresh_venues().await?;
+        Ok(self.ticker_all_venues(symbol).await)
+    }
+
+    /// Ticker and order book of `symbol` on each of `venues`. Venues that
+    /// fail are reported in `failures` instead of failing the table.
+    ///
+    /// The requests are issued together but every one waits for the
+    /// client's rate limiter, so they are sent one after another: two per
+    /// venue, about 14 seconds for 7 venues at the default 1 request per
+    /// second. The limit is per IP address, a client allowed more can pass
+    /// a faster limiter to `RestClient::with_rate_limiter`.
+    pub async fn ticker_venues(&self, symbol: &str, venues: &[&str]) -> TickerTable {
+        let results = join_all(venues.iter().map(|venue| async move {
+            let (ticker, book) =
+                tokio::join!(self.ticker(symbol, venue), self.order_book(symbol, venue));
+            (venue.to_string(), ticker, book)
+        }))
+        .await;
+        merge(symbol, results)
+    }
+}
+
+fn merge(symbol: &str, results: Vec<(String, Result<Ticker>, Result<OrderBook>)>) -> TickerTable {
+    let mut table = TickerTable {
+        symbol: symbol.to_string(),
+        quotes: vec![],
+        failures: vec![],
+    };
+    for (venue, ticker, book) in results {
+        let ticker = match ticker {
+            Ok(ticker) => ticker,
+            Err(e) => {
+                table.failures.push((venue, e));
+                continue;
+            }
+        };
+        let (bid, ask) = match book {
+            Ok(book) => top_of_book(&book),
+            Err(e) => {
+                table.failures.push((venue.clone(), e));
+                (None, None)
+            }
+        };
+        table.quotes.push(VenueQuote {
+            venue,
+            ticker,
+            bid,
+            ask,
+        });
+    }
+    table
+}
+
+fn top_of_book(book: &OrderBook) -> (Option<PriceSizePair>, Option<PriceSizePair>) {
+    let bid = book
+        .bids()
+        .iter()
+        .max_by(|a, b| a.price().total_cmp(&b.price()));
+    let ask = book
+        .asks()
+        .iter()
+        .min_by(|a, b| a.price().total_cmp(&b.price()));
+    (bid.cloned(), ask.cloned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn quote(venue: &str, close: f64, vol: f64, bid: f64, ask: f64) -> VenueQuote {
+        let ticker = serde_json::from_value(json!({
+            "symbol": "BTCUSD",
+            "open": close,
+            "high": close,
+            "low": close,
+            "close": close,
+            "vol": vol,
+            "amount": close * vol,
+            "count": 1,
+            "provider": venue,
+            "tickerTime": 0,
+            "updateAt": 0,
+        }))
+        .unwrap();
+        VenueQuote {
+            venue: venue.to_string(),
+            ticker,
+            bid: Some(PriceSizePair::new(bid, 1.0)),
+            ask: Some(PriceSizePair::new(ask, 1.0)),
+        }
+    }
+
+    #[test]
+    fn test_merge() {
+        let q = quote("BINANCE", 100.0, 1.0, 99.0, 101.0);
+        let book: OrderBook = serde_json::from_value(json!({
+            "symbol": "BTCUSD",
+            "updatedAt": 0,
+            "asks": [[101.0, 1.0], [102.0, 2.0]],
+            "bids": [[99.0, 1.0], [98.0, 2.0]],
+        }))
+        .unwrap();
+        let results = vec![
+            ("BINANCE".to_string(), Ok(q.ticker.clone()), Ok(book)),
+            (
+                "HUOBI".to_string(),
+                Ok(q.ticker.clone()),
+                Err(Error::EmptyResult()),
+            ),
+            (
+                "OKEX".to_string(),
+                Err(Error::EmptyResult()),
+                Err(Error::EmptyResult()),
+            ),
+        ];
+
+        let table = merge("BTCUSD", results);
+        assert_eq!(table.quotes.len(), 2);
+        assert_eq!(table.quotes[0].bid.as_ref().unwrap().price(), 99.0);
+        assert!(table.quotes[1].bid.is_none());
+        let failed: Vec<_> = table.failures.iter().map(|(v, _)| v.as_str()).collect();
+        assert_eq!(failed, vec!["HUOBI", "OKEX"]);
+    }
+
+    #[test]
+    fn test_ticker_table() {
+        let table = TickerTable {
+            symbol: "BTCUSD".to_string(),
+            quotes: vec![
+                quote("BINANCE", 100.0, 3.0, 99.0, 101.0),
+                quote("HUOBI", 104.0, 1.0, 100.0, 102.0),
+            ],
+            failures: vec![],
+        };
+        assert_eq!(table.best_bid().unwrap().0, "HUOBI");
+        assert_eq!(table.best_ask().unwrap().0, "BINANCE");
+        assert_eq!(table.volume_weighted_price(), Some(101.0));
+    }
+}
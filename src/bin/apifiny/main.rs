@@ -0,0 +1,439 @@
+use apifiny::{
+    history::HistoryQuery,
+    rest_client::{
+        CreateConversionParams, CreateOrderParams, ListCompletedOrderParams, ListFilledOrderParams,
+        OrderInfo, TransferBetweenVenuesParams,
+    },
+    treasury::{Allowlist, HttpApprovalHook, Treasury},
+    venues::{VenueRegistry, VenueUrls},
+    ApiFiny, Coin, RestClient, Result,
+};
+use chrono::{DateTime, Duration, Utc};
+use clap::{Parser, Subcommand};
+use dotenv::dotenv;
+use futures::TryStreamExt;
+use output::Format;
+
+mod output;
+#[cfg(feature = "tui")]
+mod tui;
+
+/// Apifiny REST api from the command line. Credentials are read from the
+/// environment or a .env file.
+#[derive(Debug, Parser)]
+#[clap(name = "apifiny", version)]
+struct Cli {
+    #[clap(flatten)]
+    conf: ApiFiny,
+    #[clap(flatten)]
+    urls: VenueUrls,
+    /// venue of venue specific commands
+    #[clap(
+        long,
+        short,
+        global = true,
+        env = "APIFINY_VENUE",
+        default_value = "BINANCE"
+    )]
+    venue: String,
+    #[clap(long, short, global = true, arg_enum, default_value = "json")]
+    output: Format,
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// List venues and their status
+    Venues,
+    /// List currencies of the venue
+    Currencies,
+    /// List symbols of the venue
+    Symbols,
+    /// Server time in milliseconds
+    Time,
+    /// Order book of a symbol
+    Book { symbol: String },
+    /// Recent trades of a symbol
+    Trades { symbol: String },
+    /// Candles of a currency pair
+    Kline {
+        base: String,
+        quote: String,
+        /// 1m, 5m, 15m, 30m, 1h, 4h, 1d, 1w or 1M
+        #[clap(long, short, default_value = "1m")]
+        period: String,
+        /// rfc 3339, with --end
+        #[clap(long, requires = "end")]
+        start: Option<DateTime<Utc>>,
+        #[clap(long, requires = "start")]
+        end: Option<DateTime<Utc>>,
+    },
+    /// 24h ticker of a symbol
+    Ticker { symbol: String },
+    /// Order book of a symbol consolidated across venues
+    Cob { symbol: String },
+    /// Account and sub accounts
+    Account,
+    /// Balances on the venue
+    Balance,
+    /// Deposit address of a coin, e.g. USDT.ETH
+    Address { coin: Coin },
+    /// Withdraw to an allowlisted address after checking the currency limits
+    Withdraw {
+        coin: Coin,
+        amount: f64,
+        address: String,
+        #[clap(long)]
+        memo: Option<String>,
+        /// allowlist file with the approval thresholds
+        #[clap(long, env = "APIFINY_ALLOWLIST")]
+        allowlist: std::path::PathBuf,
+        /// approval service asked above the thresholds
+        #[clap(long, env = "APIFINY_APPROVAL_URL")]
+        approval_url: Option<String>,
+        /// operator asking for the withdrawal, can't approve it as well
+        #[clap(long, env = "USER")]
+        requester: String,
+    },
+    /// Transfer to another venue
+    Transfer {
+        currency: String,
+        amount: f64,
+        target_venue: String,
+    },
+    /// Account activities, the last 30 days by default
+    History {
+        #[clap(long)]
+        start: Option<DateTime<Utc>>,
+        #[clap(long)]
+        end: Option<DateTime<Utc>>,
+    },
+    /// Commission rates of a symbol
+    Fees { symbol: String },
+    /// Convert between USD and USDC
+    Convert {
+        currency: String,
+        target_currency: String,
+        amount: f64,
+    },
+    /// Create, cancel and list orders
+    #[clap(subcommand)]
+    Order(OrderCommand),
+    /// Interactive terminal with market, orders and balances of a symbol
+    #[cfg(feature = "tui")]
+    Tui {
+        symbol: String,
+        /// seconds between refreshes, at least 1. The ticker and order book
+        /// share the 1 request per second public limit, so a refresh takes
+        /// 2 seconds at least
+        #[clap(long, default_value = "2")]
+        interval: u64,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum OrderCommand {
+    /// Place an order
+    New {
+        symbol: String,
+        /// BUY or SELL
+        side: String,
+        quantity: String,
+        /// LIMIT, MARKET, STOP or SOR
+        #[clap(long = "type", default_value = "LIMIT")]
+        order_type: String,
+        /// limit price, required by LIMIT orders
+        #[clap(long, default_value = "")]
+        price: String,
+        /// 1 GTC, 3 IOC, 7 post only
+        #[clap(long, default_value = "1")]
+        time_in_force: i32,
+        /// quote amount of MARKET buys
+        #[clap(long)]
+        total: Option<String>,
+        /// trigger price of STOP orders, with --stop-type
+        #[clap(long, requires = "stop-type")]
+        trigger: Option<String>,
+        /// ENTRY or LOSS
+        #[clap(long, requires = "trigger")]
+        stop_type: Option<String>,
+        #[clap(long)]
+        order_id: Option<String>,
+    },
+    /// Cancel an order, or all orders of a symbol
+    Cancel {
+        #[clap(required_unless_present = "all")]
+        order_id: Option<String>,
+        /// symbol to cancel every open order of
+        #[clap(long, conflicts_with = "order-id")]
+        all: Option<String>,
+    },
+    /// Orders by id
+    Get {
+        #[clap(required = true)]
+        order_ids: Vec<String>,
+    },
+    /// Open, completed or filled orders
+    List {
+        #[clap(long, arg_enum, default_value = "open")]
+        status: ListStatus,
+        #[clap(long)]
+        symbol: Option<String>,
+        #[clap(long)]
+        start: Option<DateTime<Utc>>,
+        #[clap(long)]
+        end: Option<DateTime<Utc>>,
+        #[clap(long)]
+        limit: Option<i32>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, clap::ArgEnum)]
+enum ListStatus {
+    Open,
+    Completed,
+    Filled,
+}
+
+#[tokio::main]
+async fn main() {
+    dotenv().ok();
+    tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .with_writer(std::io::stderr)
+        .init();
+
+    let cli = Cli::parse();
+    if let Err(e) = run(cli).await {
+        eprintln!("error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+async fn run(cli: Cli) -> Result<()> {
+    let format = cli.output;
+    let client = RestClient::new(cli.conf)?.with_venue_registry(VenueRegistry::new(cli.urls));
+    let venue = cli.venue.to_uppercase();
+    let venue = venue.as_str();
+    // the account's sub account on the venue
+    let private = || client.venue_named(venue);
+
+    match cli.command {
+        Command::Venues => {
+            output::print(format, &client.list_venue_info(venue).await?.into_result()?)
+        }
+        Command::Currencies => {
+            output::print(format, &client.list_currency(venue).await?.into_result()?)
+        }
+        Command::Symbols => output::print(
+            format,
+            &client.list_symbol_info(venue).await?.into_result()?,
+        ),
+        Command::Time => output::print(
+            format,
+            &client.current_time_millis(venue).await?.into_result()?,
+        ),
+        Command::Book { symbol } => {
+            output::print(format, &client.order_book(&symbol, venue).await?)
+        }
+        Command::Trades { symbol } => output::print(format, &client.trade(&symbol, venue).await?),
+        Command::Kline {
+            base,
+            quote,
+            period,
+            start,
+            end,
+        } => output::print(
+            format,
+            &client
+                .kline(venue, &base, &quote, &period, start, end)
+                .await?,
+        ),
+        Command::Ticker { symbol } => output::print(format, &client.ticker(&symbol, venue).await?),
+        Command::Cob { symbol } => {
+            output::print(format, &client.consolidated_order_book(&symbol).await?)
+        }
+        Command::Account => output::print(
+            format,
+            &private()?.query_account_info().await?.into_result()?,
+        ),
+        Command::Balance => output::print(format, &private()?.list_balance().await?.into_result()?),
+        Command::Address { coin } => output::print(
+            format,
+            &private()?
+                .query_address(&coin.to_string())
+                .await?
+                .into_result()?,
+        ),
+        Command::Withdraw {
+            coin,
+            amount,
+            address,
+            memo,
+            allowlist,
+            approval_url,
+            requester,
+        } => {
+            let mut treasury = Treasury::new(private()?, Allowlist::load(allowlist)?);
+            if let Some(url) = approval_url {
+                treasury = treasury.with_approval_hook(HttpApprovalHook::new(&url)?);
+            }
+            let handle = treasury
+                .withdraw(&requester, &coin, amount, &address, memo.as_deref())
+                .await?;
+            output::print(format, handle.withdraw())
+        }
+        Command::Transfer {
+            currency,
+            amount,
+            target_venue,
+        } => {
+            let params = TransferBetweenVenuesParams {
+                currency,
+                amount,
+                target_venue: target_venue.to_uppercase(),
+            };
+            output::print(
+                format,
+                &private()?.transfer_to_venue(params).await?.into_result()?,
+            )
+        }
+        Command::History { start, end } => {
+            let end = end.unwrap_or_else(|| client.clock().now());
+            let start = start.unwrap_or(end - Duration::days(30));
+            let history: Vec<_> = private()?
+                .account_history_query(HistoryQuery::new(start, end))
+                .try_collect()
+                .await?;
+            output::print(format, &history)
+        }
+        Command::Fees { symbol } => output::print(
+            format,
+            &private()?
+                .get_commission_rate(symbol)
+                .await?
+                .into_result()?,
+        ),
+        Command::Convert {
+            currency,
+            target_currency,
+            amount,
+        } => {
+            let params = CreateConversionParams::new(&currency, &target_currency, amount);
+            output::print(
+                format,
+                &private()?.create_conversion(params).await?.into_result()?,
+            )
+        }
+        Command::Order(command) => order_command(private()?, format, command).await,
+        #[cfg(feature = "tui")]
+        Command::Tui { symbol, interval } => {
+            let interval = std::time::Duration::from_secs(interval);
+            tui::run(client.clone(), &symbol, venue, interval).await
+        }
+    }
+}
+
+async fn order_command(
+    client: apifiny::VenueClient<'_>,
+    format: Format,
+    command: OrderCommand,
+) -> Result<()> {
+    match command {
+        OrderCommand::New {
+            symbol,
+            side,
+            quantity,
+            order_type,
+            price,
+            time_in_force,
+            total,
+            trigger,
+            stop_type,
+            order_id,
+        } => {
+            let mut info = OrderInfo::new(&symbol, &order_type, &side, &price, &quantity)
+                .time_in_force(time_in_force);
+            if let Some(total) = total {
+                info = info.total(&total);
+            }
+            if let (Some(trigger), Some(stop_type)) = (trigger, stop_type) {
+                info = info.trigger(&trigger, &stop_type);
+            }
+            let mut params = CreateOrderParams::new(info);
+            if let Some(order_id) = order_id {
+                params = params.order_id(&order_id);
+            }
+            output::print(format, &client.create_order(params).await?)
+        }
+        OrderCommand::Cancel { order_id, all } => match (order_id, all) {
+            (Some(order_id), _) => output::print(format, &client.cancel_order(&order_id).await?),
+            (None, Some(symbol)) => output::print(
+                format,
+                &client.cancel_account_venue_all_order(&symbol).await?,
+            ),
+            (None, None) => unreachable!("clap requires an order id or --all"),
+        },
+        OrderCommand::Get { mut order_ids } => {
+            if order_ids.len() == 1 {
+                output::print(
+                    format,
+                    &client.query_order_info(&order_ids.remove(0)).await?,
+                )
+            } else {
+                output::print(format, &client.list_order_by_ids(order_ids).await?)
+            }
+        }
+        OrderCommand::List {
+            status,
+            symbol,
+            start,
+            end,
+            limit,
+        } => {
+            let orders = match status {
+                ListStatus::Open => client.list_open_order().await?,
+                ListStatus::Completed => {
+                    let mut params = ListCompletedOrderParams::default();
+                    if let Some(start) = start {
+                        params = params.start_time(start);
+                    }
+                    if let Some(end) = end {
+                        params = params.end_time(end);
+                    }
+                    if let Some(limit) = limit {
+                        params = params.limit(limit);
+                    }
+                    client.list_completed_order(params).await?
+                }
+                ListStatus::Filled => {
+                    let mut params = ListFilledOrderParams::default();
+                    if let Some(symbol) = &symbol {
+                        params = params.symbol(symbol);
+                    }
+                    if let Some(start) = start {
+                        params = params.start_time(start);
+                    }
+                    if let Some(end) = end {
+                        params = params.end_time(end);
+                    }
+                    if let Some(limit) = limit {
+                        params = params.limit(limit);
+                    }
+                    client.list_filled_order(params).await?
+                }
+            };
+            // the open and completed lists have no symbol filter
+            let orders: Vec<_> = orders
+                .into_iter()
+                .filter(|o| {
+                    symbol
+                        .as_ref()
+                        .is_none_or(|s| o.symbol().eq_ignore_ascii_case(s))
+                })
+                .collect();
+            output::print(format, &orders)
+        }
+    }
+}
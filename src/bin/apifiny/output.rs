@@ -0,0 +1,132 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::io::Write;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ArgEnum)]
+pub enum Format {
+    Json,
+    Table,
+    Csv,
+}
+
+// print `value` to stdout in `format`
+pub fn print<T: Serialize>(format: Format, value: &T) -> apifiny::Result<()> {
+    let mut out = std::io::stdout().lock();
+    write(&mut out, format, value)
+}
+
+pub fn write<T: Serialize>(out: &mut impl Write, format: Format, value: &T) -> apifiny::Result<()> {
+    let value = serde_json::to_value(value)?;
+    match format {
+        Format::Json => {
+            serde_json::to_writer_pretty(&mut *out, &value)?;
+            writeln!(out)?;
+        }
+        Format::Table => {
+            let (columns, rows) = rows(value);
+            let mut widths: Vec<usize> = columns.iter().map(|c| c.chars().count()).collect();
+            for row in &rows {
+                for (width, cell) in widths.iter_mut().zip(row) {
+                    *width = (*width).max(cell.chars().count());
+                }
+            }
+            for row in std::iter::once(&columns).chain(&rows) {
+                let line: Vec<String> = row
+                    .iter()
+                    .zip(&widths)
+                    .map(|(cell, width)| format!("{:width$}", cell, width = width))
+                    .collect();
+                writeln!(out, "{}", line.join("  ").trim_end())?;
+            }
+        }
+        Format::Csv => {
+            let (columns, rows) = rows(value);
+            for row in std::iter::once(&columns).chain(&rows) {
+                let line: Vec<String> = row.iter().map(|cell| csv_field(cell)).collect();
+                writeln!(out, "{}", line.join(","))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+// Columns and rows of a json value: one row per array element or object,
+// columns in order of first appearance. Nested values stay json.
+fn rows(value: Value) -> (Vec<String>, Vec<Vec<String>>) {
+    let records = match value {
+        Value::Array(values) => values,
+        value => vec![value],
+    };
+
+    let mut columns: Vec<String> = vec![];
+    for record in &records {
+        match record {
+            Value::Object(map) => {
+                for key in map.keys() {
+                    if !columns.contains(key) {
+                        columns.push(key.clone());
+                    }
+                }
+            }
+            _ if columns.is_empty() => columns.push("value".to_string()),
+            _ => {}
+        }
+    }
+
+    let rows = records
+        .iter()
+        .map(|record| match record {
+            Value::Object(map) => columns
+                .iter()
+                .map(|c| map.get(c).map(cell).unwrap_or_default())
+                .collect(),
+            value => vec![cell(value)],
+        })
+        .collect();
+    (columns, rows)
+}
+
+fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        value => value.to_string(),
+    }
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn render(format: Format, value: Value) -> String {
+        let mut out = vec![];
+        write(&mut out, format, &value).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_formats() {
+        let value = json!([
+            {"currency": "BTC", "amount": 1.5},
+            {"currency": "USDT", "amount": 20, "memo": "a, \"b\""},
+        ]);
+        assert_eq!(
+            render(Format::Table, value.clone()),
+            "amount  currency  memo\n1.5     BTC\n20      USDT      a, \"b\"\n"
+        );
+        assert_eq!(
+            render(Format::Csv, value),
+            "amount,currency,memo\n1.5,BTC,\n20,USDT,\"a, \"\"b\"\"\"\n"
+        );
+        assert_eq!(render(Format::Csv, json!(42)), "value\n42\n");
+    }
+}
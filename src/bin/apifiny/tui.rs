@@ -0,0 +1,536 @@
+use apifiny::{
+    rest_client::{Balance, CreateOrderParams, OrderBook, OrderInfo, OrderResponse, Ticker},
+    Error, RestClient, Result,
+};
+use crossterm::event::{Event, EventStream, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use futures::StreamExt;
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Block, Cell, Paragraph, Row, Table},
+    DefaultTerminal, Frame,
+};
+use std::{sync::Arc, time::Duration};
+use tokio::{
+    sync::{mpsc, watch, Notify},
+    time::MissedTickBehavior,
+};
+
+// order book levels shown per side
+const BOOK_DEPTH: usize = 10;
+
+const HELP: &str =
+    "buy QTY PRICE | sell QTY PRICE | cancel ID | cancel all | symbol SYM | venue VENUE | quit";
+
+// the symbol and venue the panels show
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Market {
+    symbol: String,
+    venue: String,
+}
+
+// polled data of one market
+enum Update {
+    Ticker(Market, Result<Ticker>),
+    Book(Market, Result<OrderBook>),
+    Orders(Market, Result<Vec<OrderResponse>>),
+    Balances(Market, Result<Vec<Balance>>),
+}
+
+// a command line
+#[derive(Debug, PartialEq, Eq)]
+enum Action {
+    // limit order
+    Order {
+        side: String,
+        quantity: String,
+        price: String,
+    },
+    Cancel(String),
+    CancelAll,
+    Symbol(String),
+    Venue(String),
+    Help,
+    Quit,
+}
+
+impl Action {
+    // question asked before orders and cancels are sent
+    fn confirmation(&self, market: &Market) -> Option<String> {
+        match self {
+            Action::Order {
+                side,
+                quantity,
+                price,
+            } => Some(format!(
+                "{} {} {} at {} on {}?",
+                side, quantity, market.symbol, price, market.venue
+            )),
+            Action::Cancel(order_id) => Some(format!("cancel order {}?", order_id)),
+            Action::CancelAll => Some(format!(
+                "cancel every open {} order on {}?",
+                market.symbol, market.venue
+            )),
+            _ => None,
+        }
+    }
+}
+
+fn parse(line: &str) -> std::result::Result<Action, String> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let number = |s: &str| {
+        s.parse::<f64>()
+            .ok()
+            .filter(|n| *n > 0.0)
+            .map(|_| s.to_string())
+            .ok_or_else(|| format!("not a positive number: {}", s))
+    };
+    match words.as_slice() {
+        [side @ ("buy" | "sell"), quantity, price] => Ok(Action::Order {
+            side: side.to_uppercase(),
+            quantity: number(quantity)?,
+            price: number(price)?,
+        }),
+        ["cancel", "all"] => Ok(Action::CancelAll),
+        ["cancel", order_id] => Ok(Action::Cancel(order_id.to_string())),
+        ["symbol", symbol] => Ok(Action::Symbol(symbol.to_uppercase())),
+        ["venue", venue] => Ok(Action::Venue(venue.to_uppercase())),
+        ["help"] => Ok(Action::Help),
+        ["quit" | "q"] => Ok(Action::Quit),
+        [] => Err(String::new()),
+        _ => Err(format!("unknown command, {}", HELP)),
+    }
+}
+
+struct App {
+    client: RestClient,
+    market: Market,
+    ticker: Option<Ticker>,
+    book: Option<OrderBook>,
+    orders: Vec<OrderResponse>,
+    balances: Vec<Balance>,
+    input: String,
+    // action waiting for a y/n answer
+    pending: Option<Action>,
+    status: String,
+    quit: bool,
+}
+
+impl App {
+    fn apply(&mut self, update: Update) {
+        let result = match update {
+            Update::Ticker(m, r) if m == self.market => r.map(|t| self.ticker = Some(t)),
+            Update::Book(m, r) if m == self.market => r.map(|b| self.book = Some(b)),
+            Update::Orders(m, r) if m == self.market => r.map(|o| self.orders = o),
+            Update::Balances(m, r) if m == self.market => r.map(|b| self.balances = b),
+            // polled before the market changed
+            _ => return,
+        };
+        if let Err(e) = result {
+            self.status = e.to_string();
+        }
+    }
+
+    fn set_market(&mut self, market: Market, markets: &watch::Sender<Market>) {
+        self.market = market.clone();
+        self.ticker = None;
+        self.book = None;
+        self.orders.clear();
+        self.balances.clear();
+        markets.send_replace(market);
+    }
+
+    // the action to run once a key completes and confirms it
+    fn on_key(&mut self, key: KeyEvent, markets: &watch::Sender<Market>) -> Option<Action> {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            self.quit = true;
+            return None;
+        }
+
+        if let Some(action) = self.pending.take() {
+            if matches!(key.code, KeyCode::Char('y' | 'Y')) {
+                return Some(action);
+            }
+            self.status = "not sent".to_string();
+            return None;
+        }
+
+        match key.code {
+            KeyCode::Char(c) => self.input.push(c),
+            KeyCode::Backspace => {
+                self.input.pop();
+            }
+            KeyCode::Esc => self.input.clear(),
+            KeyCode::Enter => {
+                let line = std::mem::take(&mut self.input);
+                match parse(&line) {
+                    Ok(Action::Symbol(symbol)) => {
+                        let market = Market {
+                            symbol,
+                            venue: self.market.venue.clone(),
+                        };
+                        self.set_market(market, markets);
+                    }
+                    Ok(Action::Venue(venue)) => match self.client.venues().get(&venue) {
+                        Ok(v) => {
+                            let market = Market {
+                                symbol: self.market.symbol.clone(),
+                                venue: v.name.to_string(),
+                            };
+                            self.set_market(market, markets);
+                        }
+                        Err(e) => self.status = e.to_string(),
+                    },
+                    Ok(Action::Help) => self.status = HELP.to_string(),
+                    Ok(Action::Quit) => self.quit = true,
+                    Ok(action) => {
+                        self.status = action.confirmation(&self.market).unwrap_or_default();
+                        self.pending = Some(action);
+                    }
+                    Err(e) => self.status = e,
+                }
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let [header, body, input, status] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Min(8),
+            Constraint::Length(3),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let [book, orders, balances] = Layout::horizontal([
+            Constraint::Percentage(30),
+            Constraint::Percentage(45),
+            Constraint::Percentage(25),
+        ])
+        .areas(body);
+
+        self.render_ticker(frame, header);
+        self.render_book(frame, book);
+        self.render_orders(frame, orders);
+        self.render_balances(frame, balances);
+
+        let title = if self.pending.is_some() {
+            "confirm [y/N]"
+        } else {
+            "command, help for the list"
+        };
+        frame.render_widget(
+            Paragraph::new(self.input.as_str()).block(Block::bordered().title(title)),
+            input,
+        );
+        frame.render_widget(Paragraph::new(self.status.as_str()), status);
+    }
+
+    fn render_ticker(&self, frame: &mut Frame, area: Rect) {
+        let line = match &self.ticker {
+            Some(t) => Line::from(format!(
+                "last {}  open {}  high {}  low {}  vol {}",
+                t.close(),
+                t.open(),
+                t.high(),
+                t.low(),
+                t.vol()
+            )),
+            None => Line::from("loading").dim(),
+        };
+        let title = format!("{} on {}", self.market.symbol, self.market.venue);
+        frame.render_widget(
+            Paragraph::new(line).block(Block::bordered().title(title)),
+            area,
+        );
+    }
+
+    fn render_book(&self, frame: &mut Frame, area: Rect) {
+        let mut rows = vec![];
+        if let Some(book) = &self.book {
+            let mut asks = book.asks().to_vec();
+            asks.sort_by(|a, b| a.price().total_cmp(&b.price()));
+            asks.truncate(BOOK_DEPTH);
+            let mut bids = book.bids().to_vec();
+            bids.sort_by(|a, b| b.price().total_cmp(&a.price()));
+            bids.truncate(BOOK_DEPTH);
+
+            for ask in asks.iter().rev() {
+                rows.push(
+                    Row::new([ask.price().to_string(), ask.size().to_string()])
+                        .style(Style::new().red()),
+                );
+            }
+            for bid in &bids {
+                rows.push(
+                    Row::new([bid.price().to_string(), bid.size().to_string()])
+                        .style(Style::new().green()),
+                );
+            }
+        }
+        let table = Table::new(rows, [Constraint::Fill(1), Constraint::Fill(1)])
+            .header(Row::new(["price", "size"]).bold())
+            .block(Block::bordered().title("book"));
+        frame.render_widget(table, area);
+    }
+
+    fn render_orders(&self, frame: &mut Frame, area: Rect) {
+        let rows = self.orders.iter().map(|o| {
+            Row::new([
+                Cell::from(o.order_id().to_string()),
+                Cell::from(o.symbol().to_string()),
+                Cell::from(o.order_side().to_string()),
+                Cell::from(o.limit_price().map(|p| p.to_string()).unwrap_or_default()),
+                Cell::from(format!(
+                    "{}/{}",
+                    o.filled_cumulative_quantity(),
+                    o.quantity()
+                )),
+            ])
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Fill(2),
+                Constraint::Fill(1),
+                Constraint::Length(4),
+                Constraint::Fill(1),
+                Constraint::Fill(1),
+            ],
+        )
+        .header(Row::new(["id", "symbol", "side", "price", "filled"]).bold())
+        .block(Block::bordered().title("open orders"));
+        frame.render_widget(table, area);
+    }
+
+    fn render_balances(&self, frame: &mut Frame, area: Rect) {
+        let rows = self.balances.iter().filter(|b| b.amount() != 0.0).map(|b| {
+            Row::new([
+                b.currency().to_string(),
+                b.available().to_string(),
+                b.frozen().to_string(),
+            ])
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Fill(1),
+                Constraint::Fill(1),
+                Constraint::Fill(1),
+            ],
+        )
+        .header(Row::new(["currency", "available", "frozen"]).bold())
+        .block(Block::bordered().title("balances"));
+        frame.render_widget(table, area);
+    }
+}
+
+// send a confirmed order or cancel on `market`
+async fn execute(client: RestClient, market: Market, action: Action) -> Result<String> {
+    let client = client.venue_named(&market.venue)?;
+    match action {
+        Action::Order {
+            side,
+            quantity,
+            price,
+        } => {
+            let info = OrderInfo::new(&market.symbol, "LIMIT", &side, &price, &quantity);
+            let order = client.create_order(CreateOrderParams::new(info)).await?;
+            Ok(format!(
+                "placed {} {}",
+                order.order_id(),
+                order.order_status()
+            ))
+        }
+        Action::Cancel(order_id) => {
+            let order = client.cancel_order(&order_id).await?;
+            Ok(format!(
+                "cancelled {} {}",
+                order.order_id(),
+                order.order_status()
+            ))
+        }
+        Action::CancelAll => {
+            let ids = client
+                .cancel_account_venue_all_order(&market.symbol)
+                .await?;
+            Ok(format!("cancelled {} orders", ids.len()))
+        }
+        _ => Ok(String::new()),
+    }
+}
+
+// Poll the market and account of the current market every `interval`, and
+// right away when the market changes or `refresh` is notified. The ticker
+// and book wait for the client's rate limiter, a round takes longer than
+// `interval` if the limiter is slower and the next one follows right away.
+fn spawn_poller(
+    client: RestClient,
+    mut markets: watch::Receiver<Market>,
+    refresh: Arc<Notify>,
+    updates: mpsc::UnboundedSender<Update>,
+    interval: Duration,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut rounds = tokio::time::interval(interval);
+        rounds.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        rounds.reset();
+        loop {
+            let m = markets.borrow_and_update().clone();
+            let account = async {
+                let venue = client.venue_named(&m.venue)?;
+                Ok::<_, Error>(tokio::join!(venue.list_open_order(), venue.list_balance()))
+            };
+            let (ticker, book, account) = tokio::join!(
+                client.ticker(&m.symbol, &m.venue),
+                client.order_book(&m.symbol, &m.venue),
+                account
+            );
+            let mut round = vec![
+                Update::Ticker(m.clone(), ticker),
+                Update::Book(m.clone(), book),
+            ];
+            match account {
+                Ok((orders, balances)) => {
+                    round.push(Update::Orders(m.clone(), orders));
+                    round.push(Update::Balances(m, balances.and_then(|b| b.into_result())));
+                }
+                // the venue lookup failed, report it once and let both
+                // panels keep their last values
+                Err(e) => round.push(Update::Orders(m, Err(e))),
+            }
+
+            // the ui is gone
+            if round.into_iter().any(|u| updates.send(u).is_err()) {
+                return;
+            }
+
+            tokio::select! {
+                _ = rounds.tick() => {}
+                _ = markets.changed() => rounds.reset(),
+                _ = refresh.notified() => rounds.reset(),
+            }
+        }
+    })
+}
+
+// Interactive terminal on `symbol` and `venue` until quit.
+pub async fn run(client: RestClient, symbol: &str, venue: &str, interval: Duration) -> Result<()> {
+    if interval.is_zero() {
+        return Err(Error::InvalidArgument(
+            "refresh interval must be positive".to_string(),
+        ));
+    }
+    let venue = client.venues().get(venue)?;
+    let market = Market {
+        symbol: symbol.to_uppercase(),
+        venue: venue.name.to_string(),
+    };
+    let (markets, markets_rx) = watch::channel(market.clone());
+    let (updates_tx, updates) = mpsc::unbounded_channel();
+    let refresh = Arc::new(Notify::new());
+    let poller = spawn_poller(
+        client.clone(),
+        markets_rx,
+        refresh.clone(),
+        updates_tx,
+        interval,
+    );
+
+    let app = App {
+        client,
+        market,
+        ticker: None,
+        book: None,
+        orders: vec![],
+        balances: vec![],
+        input: String::new(),
+        pending: None,
+        status: HELP.to_string(),
+        quit: false,
+    };
+    let mut terminal = ratatui::try_init()?;
+    let result = event_loop(&mut terminal, app, &markets, updates, &refresh).await;
+    ratatui::restore();
+    poller.abort();
+    result
+}
+
+async fn event_loop(
+    terminal: &mut DefaultTerminal,
+    mut app: App,
+    markets: &watch::Sender<Market>,
+    mut updates: mpsc::UnboundedReceiver<Update>,
+    refresh: &Notify,
+) -> Result<()> {
+    let mut events = EventStream::new();
+    // orders and cancels run in their own task so the ui keeps updating
+    let (done_tx, mut done) = mpsc::unbounded_channel::<Result<String>>();
+    while !app.quit {
+        terminal.draw(|frame| app.render(frame))?;
+        tokio::select! {
+            Some(update) = updates.recv() => app.apply(update),
+            Some(result) = done.recv() => {
+                app.status = match result {
+                    Ok(message) => message,
+                    Err(e) => e.to_string(),
+                };
+                refresh.notify_one();
+            }
+            Some(event) = events.next() => {
+                if let Event::Key(key) = event? {
+                    if key.kind != KeyEventKind::Press {
+                        continue;
+                    }
+                    if let Some(action) = app.on_key(key, markets) {
+                        app.status = "sending".to_string();
+                        let (client, market) = (app.client.clone(), app.market.clone());
+                        let done_tx = done_tx.clone();
+                        tokio::spawn(async move {
+                            let _ = done_tx.send(execute(client, market, action).await);
+                        });
+                    }
+                }
+            }
+            else => break,
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            parse("buy 0.5 30000"),
+            Ok(Action::Order {
+                side: "BUY".to_string(),
+                quantity: "0.5".to_string(),
+                price: "30000".to_string(),
+            })
+        );
+        assert!(parse("sell -1 30000").is_err());
+        assert_eq!(parse("cancel all"), Ok(Action::CancelAll));
+        assert_eq!(parse("cancel 123"), Ok(Action::Cancel("123".to_string())));
+        assert_eq!(parse("venue huobi"), Ok(Action::Venue("HUOBI".to_string())));
+        assert!(parse("short 1").is_err());
+
+        let market = Market {
+            symbol: "BTCUSDT".to_string(),
+            venue: "BINANCE".to_string(),
+        };
+        assert_eq!(
+            parse("sell 1 31000")
+                .unwrap()
+                .confirmation(&market)
+                .unwrap(),
+            "SELL 1 BTCUSDT at 31000 on BINANCE?"
+        );
+        assert_eq!(parse("symbol ethusdt").unwrap().confirmation(&market), None);
+    }
+}
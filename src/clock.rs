@@ -0,0 +1,270 @@
+use super::{Error, RestClient, Result};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use std::sync::{
+    atomic::{AtomicI64, Ordering},
+    Arc, Mutex,
+};
+use tokio::task::JoinHandle;
+
+// Local time corrected by the measured server offset. Clones share the
+// offset, so one sync corrects every signer and client holding the clock.
+#[derive(Debug, Clone, Default)]
+pub struct Clock {
+    offset_ms: Arc<AtomicI64>,
+}
+
+impl Clock {
+    pub fn now(&self) -> DateTime<Utc> {
+        Utc::now() + self.offset()
+    }
+
+    // server time minus local time
+    pub fn offset(&self) -> Duration {
+        Duration::milliseconds(self.offset_ms.load(Ordering::Relaxed))
+    }
+
+    pub fn set_offset(&self, offset: Duration) {
+        self.offset_ms
+            .store(offset.num_milliseconds(), Ordering::Relaxed);
+    }
+}
+
+// one server time request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sample {
+    // server time minus local time, assuming the server answered halfway
+    pub offset: Duration,
+    pub round_trip: Duration,
+}
+
+impl Sample {
+    // from local send and receive times and the server's reply
+    pub fn new(sent: DateTime<Utc>, server: DateTime<Utc>, received: DateTime<Utc>) -> Sample {
+        let round_trip = received - sent;
+        Sample {
+            offset: server - (sent + round_trip / 2),
+            round_trip,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ClockSyncConfig {
+    // between syncs of a running service
+    pub interval: std::time::Duration,
+    // requests per sync, the one with the shortest round trip is kept
+    pub samples: usize,
+    // offsets beyond this raise a drift alert
+    pub drift_threshold: Duration,
+}
+
+impl Default for ClockSyncConfig {
+    fn default() -> Self {
+        ClockSyncConfig {
+            interval: std::time::Duration::from_secs(5 * 60),
+            samples: 5,
+            drift_threshold: Duration::seconds(1),
+        }
+    }
+}
+
+type DriftHook = Arc<dyn Fn(&Sample) + Send + Sync>;
+
+// Keeps the client's clock in line with `current_time_millis`.
+#[derive(Clone)]
+pub struct ClockSync {
+    client: RestClient,
+    venue: String,
+    config: ClockSyncConfig,
+    last: Arc<Mutex<Option<Sample>>>,
+    on_drift: Option<DriftHook>,
+}
+
+impl ClockSync {
+    pub fn new(client: RestClient, venue: &str, config: ClockSyncConfig) -> ClockSync {
+        ClockSync {
+            client,
+            venue: venue.to_string(),
+            config,
+            last: Arc::default(),
+            on_drift: None,
+        }
+    }
+
+    // called after a sync measured more drift than `drift_threshold`,
+    // in addition to a warning in the log
+    pub fn on_drift(mut self, hook: impl Fn(&Sample) + Send + Sync + 'static) -> ClockSync {
+        self.on_drift = Some(Arc::new(hook));
+        self
+    }
+
+    pub fn clock(&self) -> &Clock {
+        self.client.clock()
+    }
+
+    // sample the last sync applied
+    pub fn last_sample(&self) -> Option<Sample> {
+        *self.last.lock().unwrap()
+    }
+
+    pub async fn measure(&self) -> Result<Sample> {
+        let sent = Utc::now();
+        let server = self
+            .client
+            .current_time_millis(&self.venue)
+            .await?
+            .into_result()?;
+        let received = Utc::now();
+        let server = Utc
+            .timestamp_millis_opt(server)
+            .single()
+            .ok_or(Error::EmptyResult())?;
+        Ok(Sample::new(sent, server, received))
+    }
+
+    // measure `samples` times and apply the offset of the fastest round trip
+    pub async fn sync(&self) -> Result<Sample> {
+        let mut sample = self.measure().await?;
+        for _ in 1..self.config.samples {
+            let next = self.measure().await?;
+            if next.round_trip < sample.round_trip {
+                sample = next;
+            }
+        }
+
+        self.clock().set_offset(sample.offset);
+        *self.last.lock().unwrap() = Some(sample);
+        if sample.offset.num_milliseconds().abs() > self.config.drift_threshold.num_milliseconds() {
+            tracing::warn!(
+                "clock drift {}ms against {}, round trip {}ms",
+                sample.offset.num_milliseconds(),
+                self.venue,
+                sample.round_trip.num_milliseconds()
+            );
+            if let Some(hook) = &self.on_drift {
+                hook(&sample);
+            }
+        }
+        Ok(sample)
+    }
+
+    // Sync every `interval` until the task is aborted, failed syncs keep the
+    // previous offset. The time endpoint is not rate limited, so intervals
+    // under a second are refused rather than hammering it.
+    pub fn spawn(self) -> Result<JoinHandle<()>> {
+        if self.config.interval < std::time::Duration::from_secs(1) {
+            return Err(Error::InvalidArgument(format!(
+                "clock sync interval {:?} is under a second",
+                self.config.interval
+            )));
+        }
+        Ok(tokio::spawn(async move {
+            let mut interval = tokio::time::interval(self.config.interval);
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.sync().await {
+                    tracing::warn!("clock sync against {} failed: {}", self.venue, e);
+                }
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        venues::{VenueRegistry, VenueUrls},
+        ApiFiny,
+    };
+    use std::sync::atomic::AtomicUsize;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    static REQUESTS: AtomicUsize = AtomicUsize::new(0);
+
+    // a time endpoint 10 seconds ahead of the local clock
+    async fn serve() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                REQUESTS.fetch_add(1, Ordering::SeqCst);
+                let mut buf = vec![0; 8192];
+                let _ = stream.read(&mut buf).await.unwrap();
+                let body = format!(
+                    "{{\"result\":{}}}",
+                    (Utc::now() + Duration::seconds(10)).timestamp_millis()
+                );
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        format!("http://{}", addr)
+    }
+
+    async fn requests_above(n: usize) {
+        while REQUESTS.load(Ordering::SeqCst) <= n {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_spawn() {
+        let url = serve().await;
+        let client = RestClient::new(ApiFiny::new("key-1", "secret", "STA-1"))
+            .unwrap()
+            .with_venue_registry(VenueRegistry::new(VenueUrls {
+                base: url,
+                ..Default::default()
+            }));
+        let config = ClockSyncConfig {
+            interval: std::time::Duration::from_secs(60),
+            samples: 1,
+            drift_threshold: Duration::seconds(1),
+        };
+        let sync = ClockSync::new(client, "BINANCE", config.clone());
+
+        let mut fast = config.clone();
+        fast.interval = std::time::Duration::from_millis(500);
+        let fast = ClockSync::new(sync.client.clone(), "BINANCE", fast);
+        assert!(matches!(fast.spawn(), Err(Error::InvalidArgument(_))));
+
+        // the first sync runs right away
+        let handle = sync.clone().spawn().unwrap();
+        while sync.last_sample().is_none() {
+            tokio::task::yield_now().await;
+        }
+        let offset = sync.clock().offset().num_milliseconds();
+        assert!((9_000..=10_000).contains(&offset), "{}", offset);
+
+        // and the next one once the interval has passed
+        let seen = REQUESTS.load(Ordering::SeqCst);
+        tokio::time::advance(config.interval).await;
+        requests_above(seen).await;
+        handle.abort();
+    }
+
+    #[test]
+    fn test_sample() {
+        let sent = Utc.with_ymd_and_hms(2022, 1, 1, 0, 0, 0).unwrap();
+        let received = sent + Duration::milliseconds(100);
+        let server = sent + Duration::milliseconds(1050);
+
+        let sample = Sample::new(sent, server, received);
+        assert_eq!(sample.round_trip, Duration::milliseconds(100));
+        assert_eq!(sample.offset, Duration::milliseconds(1000));
+
+        let clock = Clock::default();
+        clock.set_offset(sample.offset);
+        assert_eq!(clock.offset(), Duration::milliseconds(1000));
+        assert_eq!(clock.clone().offset(), clock.offset());
+    }
+}
@@ -1,11 +1,23 @@
+use super::Secret;
 use clap::Parser;
 
-#[derive(Debug, Parser)]
+#[derive(Debug, Clone, Parser)]
 pub struct ApiFiny {
     #[clap(required = true, env)]
     pub apifiny_access_key: String,
-    #[clap(required = true, env)]
-    pub apifiny_secret_key: String,
+    #[clap(required = true, env, hide_env_values = true)]
+    pub apifiny_secret_key: Secret,
     #[clap(required = true, env)]
     pub apifiny_account_id: String,
 }
+
+impl ApiFiny {
+    // credentials without going through clap, see `credentials` for other sources
+    pub fn new(access_key: &str, secret_key: &str, account_id: &str) -> ApiFiny {
+        ApiFiny {
+            apifiny_access_key: access_key.to_string(),
+            apifiny_secret_key: Secret::new(secret_key),
+            apifiny_account_id: account_id.to_string(),
+        }
+    }
+}
@@ -1,32 +1,54 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Venue {
     pub name: &'static str,
+    // host code in the venue's api urls
+    pub code: &'static str,
     pub rest: &'static str,
     pub fix: &'static str,
 }
 
 macro_rules! venues {
-    ($($id:ident, $rest:expr, $fix:expr;)+) => {
+    ($($id:ident, $code:expr;)+) => {
         $(
-            venues!{$id, $rest, $fix}
+            venues!{$id, $code}
         )+
+
+        // every built-in venue
+        pub const VENUES: &[Venue] = &[$($id),+];
     };
 
-    ($id:ident, $rest:expr, $fix:expr) => {
+    ($id:ident, $code:expr) => {
         pub const $id: Venue = Venue {
             name: stringify!($id),
-            rest: $rest,
-            fix: $fix,
+            code: $code,
+            rest: concat!("https://api", $code, ".apifiny.com/ac/v2"),
+            fix: concat!("fixapi", $code, ".apifiny.com:1443"),
         };
     };
 }
 
 venues! {
-    BINANCE, "https://apibn.apifiny.com/ac/v2", "fixapibn.apifiny.com:1443";
-    BINANCEUS, "https://apibnu.apifiny.com/ac/v2", "fixapibnu.apifiny.com:1443";
-    COINBASEPRO, "https://apicb.apifiny.com/ac/v2", "fixapicb.apifiny.com:1443";
-    FTX, "https://apiftx.apifiny.com/ac/v2", "fixapiftx.apifiny.com:1443";
-    HUOBI, "https://apihb.apifiny.com/ac/v2", "fixapihb.apifiny.com:1443";
-    KUCOIN, "https://apikc.apifiny.com/ac/v2", "fixapikc.apifiny.com:1443";
-    OKEX, "https://apiok.apifiny.com/ac/v2", "fixapiok.apifiny.com:1443";
-    OKCOIN, "https://apiokc.apifiny.com/ac/v2", "fixapiokc.apifiny.com:1443";
+    BINANCE, "bn";
+    BINANCEUS, "bnu";
+    COINBASEPRO, "cb";
+    HUOBI, "hb";
+    KUCOIN, "kc";
+    OKEX, "ok";
+    OKCOIN, "okc";
+}
+
+#[deprecated(note = "FTX is closed, venues are listed by `VenueRegistry`")]
+pub const FTX: Venue = Venue {
+    name: "FTX",
+    code: "ftx",
+    rest: "https://apiftx.apifiny.com/ac/v2",
+    fix: "fixapiftx.apifiny.com:1443",
+};
+
+// built-in venue by name, ignoring case
+pub fn venue_by_name(name: &str) -> Option<Venue> {
+    VENUES
+        .iter()
+        .find(|v| v.name.eq_ignore_ascii_case(name))
+        .copied()
 }
@@ -0,0 +1,289 @@
+use super::{ApiFiny, Error, Result, Secret};
+use chacha20poly1305::{
+    aead::{Aead, AeadCore, KeyInit, OsRng},
+    ChaCha20Poly1305, Key, Nonce,
+};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::{
+    io::BufRead,
+    path::{Path, PathBuf},
+};
+use zeroize::Zeroizing;
+
+// where a client's access key, secret key and account id come from
+pub trait CredentialsProvider {
+    fn credentials(&self) -> Result<ApiFiny>;
+}
+
+// credentials file and keystore content
+#[derive(Serialize, Deserialize)]
+struct Stored {
+    access_key: String,
+    secret_key: Secret,
+    account_id: String,
+}
+
+impl From<Stored> for ApiFiny {
+    fn from(s: Stored) -> ApiFiny {
+        ApiFiny::new(&s.access_key, s.secret_key.expose(), &s.account_id)
+    }
+}
+
+impl From<&ApiFiny> for Stored {
+    fn from(conf: &ApiFiny) -> Stored {
+        Stored {
+            access_key: conf.apifiny_access_key.clone(),
+            secret_key: conf.apifiny_secret_key.clone(),
+            account_id: conf.apifiny_account_id.clone(),
+        }
+    }
+}
+
+// APIFINY_ACCESS_KEY, APIFINY_SECRET_KEY and APIFINY_ACCOUNT_ID
+#[derive(Debug, Clone, Default)]
+pub struct EnvCredentials;
+
+impl CredentialsProvider for EnvCredentials {
+    fn credentials(&self) -> Result<ApiFiny> {
+        let var = |name: &str| {
+            std::env::var(name).map_err(|_| Error::MissingCredential(name.to_string()))
+        };
+        let secret_key = Secret::from(var("APIFINY_SECRET_KEY")?);
+        Ok(ApiFiny::new(
+            &var("APIFINY_ACCESS_KEY")?,
+            secret_key.expose(),
+            &var("APIFINY_ACCOUNT_ID")?,
+        ))
+    }
+}
+
+// A json file with `access_key`, `secret_key` and `account_id`. On unix it
+// must not be readable by group or others.
+#[derive(Debug, Clone)]
+pub struct FileCredentials {
+    path: PathBuf,
+}
+
+impl FileCredentials {
+    pub fn new(path: impl AsRef<Path>) -> FileCredentials {
+        FileCredentials {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+}
+
+impl CredentialsProvider for FileCredentials {
+    fn credentials(&self) -> Result<ApiFiny> {
+        check_permissions(&self.path)?;
+        let data = std::fs::read(&self.path)?;
+        let stored: Stored = serde_json::from_slice(&data)?;
+        Ok(stored.into())
+    }
+}
+
+#[cfg(unix)]
+fn check_permissions(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode = std::fs::metadata(path)?.permissions().mode();
+    if mode & 0o077 != 0 {
+        return Err(Error::InsecurePermissions(format!(
+            "{} has mode {:o}, expected 600",
+            path.display(),
+            mode & 0o777
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn check_permissions(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+// access key, secret key and account id as three lines on stdin, e.g. piped
+// from a password manager
+#[derive(Debug, Clone, Default)]
+pub struct StdinCredentials;
+
+impl CredentialsProvider for StdinCredentials {
+    fn credentials(&self) -> Result<ApiFiny> {
+        read_lines(std::io::stdin().lock())
+    }
+}
+
+fn read_lines(mut reader: impl BufRead) -> Result<ApiFiny> {
+    let mut next = |name: &str| -> Result<String> {
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let line = line.trim();
+        if line.is_empty() {
+            return Err(Error::MissingCredential(name.to_string()));
+        }
+        Ok(line.to_string())
+    };
+    let access_key = next("access key")?;
+    let secret_key = Secret::from(next("secret key")?);
+    let account_id = next("account id")?;
+    Ok(ApiFiny::new(&access_key, secret_key.expose(), &account_id))
+}
+
+// pbkdf2 rounds of new keystores
+const KEYSTORE_ROUNDS: u32 = 600_000;
+
+// keystore file, hex encoded
+#[derive(Serialize, Deserialize)]
+struct KeystoreFile {
+    version: u32,
+    rounds: u32,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+// Credentials encrypted with a passphrase, ChaCha20-Poly1305 with a
+// PBKDF2-HMAC-SHA256 derived key.
+#[derive(Debug, Clone)]
+pub struct Keystore {
+    path: PathBuf,
+    passphrase: Secret,
+}
+
+impl Keystore {
+    pub fn new(path: impl AsRef<Path>, passphrase: &str) -> Keystore {
+        Keystore {
+            path: path.as_ref().to_path_buf(),
+            passphrase: Secret::new(passphrase),
+        }
+    }
+
+    // encrypt `conf` into the keystore file, readable by the owner only
+    pub fn save(&self, conf: &ApiFiny) -> Result<()> {
+        self.save_with_rounds(conf, KEYSTORE_ROUNDS)
+    }
+
+    fn save_with_rounds(&self, conf: &ApiFiny, rounds: u32) -> Result<()> {
+        let salt: [u8; 16] = rand_bytes();
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(
+            derive_key(self.passphrase.expose(), &salt, rounds).as_ref(),
+        ));
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let plaintext = Zeroizing::new(serde_json::to_vec(&Stored::from(conf))?);
+        let ciphertext = cipher
+            .encrypt(&nonce, plaintext.as_slice())
+            .map_err(|e| Error::Keystore(e.to_string()))?;
+
+        let file = KeystoreFile {
+            version: 1,
+            rounds,
+            salt: hex::encode(salt),
+            nonce: hex::encode(nonce),
+            ciphertext: hex::encode(ciphertext),
+        };
+        write_private(&self.path, &serde_json::to_vec_pretty(&file)?)
+    }
+}
+
+impl CredentialsProvider for Keystore {
+    fn credentials(&self) -> Result<ApiFiny> {
+        check_permissions(&self.path)?;
+        let file: KeystoreFile = serde_json::from_slice(&std::fs::read(&self.path)?)?;
+        if file.version != 1 {
+            return Err(Error::Keystore(format!("unknown version {}", file.version)));
+        }
+        let decode = |s: &str| hex::decode(s).map_err(|e| Error::Keystore(e.to_string()));
+        let salt = decode(&file.salt)?;
+        let nonce = decode(&file.nonce)?;
+        if nonce.len() != 12 {
+            return Err(Error::Keystore("invalid nonce".to_string()));
+        }
+
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(
+            derive_key(self.passphrase.expose(), &salt, file.rounds).as_ref(),
+        ));
+        let plaintext = Zeroizing::new(
+            cipher
+                .decrypt(
+                    Nonce::from_slice(&nonce),
+                    decode(&file.ciphertext)?.as_slice(),
+                )
+                .map_err(|_| {
+                    Error::Keystore("wrong passphrase or corrupted keystore".to_string())
+                })?,
+        );
+        let stored: Stored = serde_json::from_slice(&plaintext)?;
+        Ok(stored.into())
+    }
+}
+
+// the cipher keeps its own copy, this one is wiped once the cipher is built
+fn derive_key(passphrase: &str, salt: &[u8], rounds: u32) -> Zeroizing<[u8; 32]> {
+    let mut key = Zeroizing::new([0; 32]);
+    pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, rounds, key.as_mut());
+    key
+}
+
+fn rand_bytes<const N: usize>() -> [u8; N] {
+    use chacha20poly1305::aead::rand_core::RngCore;
+
+    let mut bytes = [0; N];
+    OsRng.fill_bytes(&mut bytes);
+    bytes
+}
+
+#[cfg(unix)]
+fn write_private(path: &Path, data: &[u8]) -> Result<()> {
+    use std::{io::Write, os::unix::fs::OpenOptionsExt};
+
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)?;
+    file.write_all(data)?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn write_private(path: &Path, data: &[u8]) -> Result<()> {
+    Ok(std::fs::write(path, data)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keystore() {
+        let path = std::env::temp_dir().join(format!("apifiny-keystore-{}", std::process::id()));
+        let conf = ApiFiny::new("key-1", "secret-1", "STA-1");
+
+        Keystore::new(&path, "passphrase")
+            .save_with_rounds(&conf, 1000)
+            .unwrap();
+        let loaded = Keystore::new(&path, "passphrase").credentials().unwrap();
+        assert_eq!(loaded.apifiny_secret_key.expose(), "secret-1");
+        assert_eq!(loaded.apifiny_account_id, "STA-1");
+        assert!(Keystore::new(&path, "wrong").credentials().is_err());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+            assert!(matches!(
+                Keystore::new(&path, "passphrase").credentials(),
+                Err(Error::InsecurePermissions(_))
+            ));
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_read_lines() {
+        let conf = read_lines("key-1\nsecret-1\nSTA-1\n".as_bytes()).unwrap();
+        assert_eq!(conf.apifiny_access_key, "key-1");
+        assert!(read_lines("key-1\n".as_bytes()).is_err());
+    }
+}
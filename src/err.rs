@@ -21,8 +21,62 @@ pub enum Error {
     Serialize(#[from] serde_json::Error),
     #[error(transparent)]
     Utf8Error(#[from] core::str::Utf8Error),
-    #[error("venue not set")]
-    VenueNotSet(),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    MsgPackEncode(#[from] rmp_serde::encode::Error),
+    #[error(transparent)]
+    MsgPackDecode(#[from] rmp_serde::decode::Error),
+    #[error(transparent)]
+    Jwt(#[from] jwt::Error),
+    #[cfg(feature = "export")]
+    #[error(transparent)]
+    Csv(#[from] csv::Error),
+    #[cfg(feature = "export")]
+    #[error(transparent)]
+    Parquet(#[from] parquet::errors::ParquetError),
+    #[error("api error, code:{code}, message:{message}")]
+    ApiError { code: i64, message: String },
+    #[error("api response carries neither result nor error")]
+    EmptyResult(),
+    #[error("invalid symbol: {0}")]
+    InvalidSymbol(String),
+    #[error("unknown symbol: {0}")]
+    UnknownSymbol(String),
+    #[error("invalid coin: {0}")]
+    InvalidCoin(String),
+    #[error("unknown coin: {0}")]
+    UnknownCoin(String),
+    #[error("invalid withdraw: {0}")]
+    InvalidWithdraw(String),
+    #[error("withdraw ticket expired")]
+    TicketExpired(),
+    #[error("withdraw address not allowlisted: {0}")]
+    NotAllowlisted(String),
+    #[error("withdraw needs approval but no approval hook is set")]
+    ApprovalRequired(),
+    #[error("withdraw rejected: {0}")]
+    ApprovalRejected(String),
+    #[error("unknown venue: {0}")]
+    UnknownVenue(String),
+    #[error("venue disabled: {0}")]
+    VenueDisabled(String),
+    #[error("timed out waiting for {0}")]
+    Timeout(String),
+    #[error("cancelled")]
+    Cancelled(),
+    #[error("signing error: {0}")]
+    Signing(String),
+    #[error("missing credential: {0}")]
+    MissingCredential(String),
+    #[error("insecure permissions: {0}")]
+    InsecurePermissions(String),
+    #[error("keystore error: {0}")]
+    Keystore(String),
+    #[error("invalid argument: {0}")]
+    InvalidArgument(String),
+    #[error("value out of range: {0}")]
+    OutOfRange(String),
 }
 
 // new http rest error
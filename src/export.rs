@@ -0,0 +1,507 @@
+use super::{
+    report::{CurrencySummary, LedgerEntry, RealizedGain},
+    rest_client::{AccountHistory, KLine, OrderResponse, TradeOrder},
+    Error, Result,
+};
+use chrono::{DateTime, SecondsFormat, Utc};
+use parquet::{
+    data_type::{ByteArray, ByteArrayType, FixedLenByteArray, FixedLenByteArrayType, Int64Type},
+    file::{properties::WriterProperties, writer::SerializedFileWriter},
+    schema::parser::parse_message_type,
+};
+use std::{io::Write, sync::Arc};
+
+// number of fractional digits kept by decimal columns
+pub const DECIMAL_SCALE: u32 = 8;
+// decimals are stored as 16 byte big endian unscaled values in parquet,
+// wide enough for any amount short of 1e30
+const DECIMAL_PRECISION: u32 = 38;
+const DECIMAL_BYTES: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnType {
+    Utf8,
+    Int64,
+    // fixed point with `DECIMAL_SCALE` fractional digits
+    Decimal,
+    // utc, millisecond precision
+    Timestamp,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Column {
+    pub name: &'static str,
+    pub kind: ColumnType,
+    pub nullable: bool,
+}
+
+const fn required(name: &'static str, kind: ColumnType) -> Column {
+    Column {
+        name,
+        kind,
+        nullable: false,
+    }
+}
+
+const fn optional(name: &'static str, kind: ColumnType) -> Column {
+    Column {
+        name,
+        kind,
+        nullable: true,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Cell {
+    Null,
+    Utf8(String),
+    Int64(i64),
+    Decimal(f64),
+    Timestamp(DateTime<Utc>),
+}
+
+impl Cell {
+    fn decimal(v: Option<f64>) -> Cell {
+        v.map(Cell::Decimal).unwrap_or(Cell::Null)
+    }
+
+    fn timestamp(v: Option<DateTime<Utc>>) -> Cell {
+        v.map(Cell::Timestamp).unwrap_or(Cell::Null)
+    }
+
+    fn utf8(v: Option<&str>) -> Cell {
+        v.map(|s| Cell::Utf8(s.to_string())).unwrap_or(Cell::Null)
+    }
+
+    fn to_csv(&self) -> String {
+        match self {
+            Cell::Null => String::new(),
+            Cell::Utf8(s) => s.clone(),
+            Cell::Int64(v) => v.to_string(),
+            Cell::Decimal(v) => format!("{:.*}", DECIMAL_SCALE as usize, v),
+            Cell::Timestamp(v) => v.to_rfc3339_opts(SecondsFormat::Millis, true),
+        }
+    }
+
+    fn to_i64(&self) -> Option<i64> {
+        match self {
+            Cell::Int64(v) => Some(*v),
+            Cell::Timestamp(v) => Some(v.timestamp_millis()),
+            _ => None,
+        }
+    }
+
+    // Unscaled big endian bytes for decimal columns. Decimals that don't fit
+    // `DECIMAL_PRECISION` digits are an error rather than a clamped value.
+    fn to_decimal_bytes(&self) -> Result<Option<FixedLenByteArray>> {
+        match self {
+            Cell::Decimal(v) => {
+                // scaled through the csv text so both exports agree digit for digit
+                let digits = self.to_csv().replace('.', "");
+                let max = 10i128.pow(DECIMAL_PRECISION);
+                match digits.parse::<i128>() {
+                    Ok(unscaled) if v.is_finite() && unscaled.abs() < max => {
+                        let bytes = unscaled.to_be_bytes().to_vec();
+                        Ok(Some(FixedLenByteArray::from(bytes)))
+                    }
+                    _ => Err(Error::OutOfRange(format!(
+                        "{} does not fit DECIMAL({}, {})",
+                        v, DECIMAL_PRECISION, DECIMAL_SCALE
+                    ))),
+                }
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+// a record with a stable column schema
+pub trait Tabular {
+    // parquet message name
+    const NAME: &'static str;
+    const COLUMNS: &'static [Column];
+
+    // one cell per column, in `COLUMNS` order
+    fn row(&self) -> Vec<Cell>;
+}
+
+pub fn write_csv<T: Tabular, W: Write>(writer: W, rows: &[T]) -> Result<()> {
+    let mut writer = csv::Writer::from_writer(writer);
+    writer.write_record(T::COLUMNS.iter().map(|c| c.name))?;
+    for row in rows {
+        writer.write_record(row.row().iter().map(Cell::to_csv))?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+// parquet schema for `T`, in the textual message type format
+pub fn parquet_schema<T: Tabular>() -> String {
+    let mut schema = format!("message {} {{\n", T::NAME);
+    for column in T::COLUMNS {
+        let repetition = if column.nullable {
+            "OPTIONAL"
+        } else {
+            "REQUIRED"
+        };
+        let (physical, logical) = match column.kind {
+            ColumnType::Utf8 => ("BINARY".to_string(), " (UTF8)".to_string()),
+            ColumnType::Int64 => ("INT64".to_string(), String::new()),
+            ColumnType::Decimal => (
+                format!("FIXED_LEN_BYTE_ARRAY({})", DECIMAL_BYTES),
+                format!(" (DECIMAL({}, {}))", DECIMAL_PRECISION, DECIMAL_SCALE),
+            ),
+            ColumnType::Timestamp => (
+                "INT64".to_string(),
+                " (TIMESTAMP(MILLIS, true))".to_string(),
+            ),
+        };
+        schema.push_str(&format!(
+            "  {} {} {}{};\n",
+            repetition, physical, column.name, logical
+        ));
+    }
+    schema.push('}');
+    schema
+}
+
+pub fn write_parquet<T: Tabular, W: Write + Send>(writer: W, rows: &[T]) -> Result<()> {
+    let schema = Arc::new(parse_message_type(&parquet_schema::<T>())?);
+    let props = Arc::new(WriterProperties::builder().build());
+    let mut writer = SerializedFileWriter::new(writer, schema, props)?;
+
+    let cells: Vec<Vec<Cell>> = rows.iter().map(Tabular::row).collect();
+    let mut row_group = writer.next_row_group()?;
+    let mut idx = 0;
+    while let Some(mut column_writer) = row_group.next_column()? {
+        let column = T::COLUMNS[idx];
+        let column_cells = cells.iter().map(|row| &row[idx]);
+        let def_levels: Vec<i16> = column_cells
+            .clone()
+            .map(|c| if *c == Cell::Null { 0 } else { 1 })
+            .collect();
+        let def_levels = if column.nullable {
+            Some(def_levels.as_slice())
+        } else {
+            None
+        };
+
+        match column.kind {
+            ColumnType::Utf8 => {
+                let values: Vec<ByteArray> = column_cells
+                    .filter_map(|c| match c {
+                        Cell::Utf8(s) => Some(ByteArray::from(s.as_str())),
+                        _ => None,
+                    })
+                    .collect();
+                column_writer
+                    .typed::<ByteArrayType>()
+                    .write_batch(&values, def_levels, None)?;
+            }
+            ColumnType::Decimal => {
+                let values = column_cells
+                    .filter_map(|c| c.to_decimal_bytes().transpose())
+                    .collect::<Result<Vec<FixedLenByteArray>>>()?;
+                column_writer
+                    .typed::<FixedLenByteArrayType>()
+                    .write_batch(&values, def_levels, None)?;
+            }
+            ColumnType::Int64 | ColumnType::Timestamp => {
+                let values: Vec<i64> = column_cells.filter_map(Cell::to_i64).collect();
+                column_writer
+                    .typed::<Int64Type>()
+                    .write_batch(&values, def_levels, None)?;
+            }
+        }
+        column_writer.close()?;
+        idx += 1;
+    }
+    row_group.close()?;
+    writer.close()?;
+    Ok(())
+}
+
+impl Tabular for KLine {
+    const NAME: &'static str = "kline";
+    const COLUMNS: &'static [Column] = &[
+        required("exchange", ColumnType::Utf8),
+        required("currency_pair", ColumnType::Utf8),
+        required("period", ColumnType::Utf8),
+        required("timestamp", ColumnType::Timestamp),
+        required("open", ColumnType::Decimal),
+        required("high", ColumnType::Decimal),
+        required("low", ColumnType::Decimal),
+        required("close", ColumnType::Decimal),
+        required("vol", ColumnType::Decimal),
+        required("count", ColumnType::Int64),
+    ];
+
+    fn row(&self) -> Vec<Cell> {
+        vec![
+            Cell::Utf8(self.exchange().to_string()),
+            Cell::Utf8(self.currency_pair().to_string()),
+            Cell::Utf8(self.period().to_string()),
+            Cell::Timestamp(self.timestamp()),
+            Cell::Decimal(self.open()),
+            Cell::Decimal(self.high()),
+            Cell::Decimal(self.low()),
+            Cell::Decimal(self.close()),
+            Cell::Decimal(self.vol()),
+            Cell::Int64(self.count()),
+        ]
+    }
+}
+
+impl Tabular for TradeOrder {
+    const NAME: &'static str = "trade";
+    const COLUMNS: &'static [Column] = &[
+        required("provider", ColumnType::Utf8),
+        required("symbol", ColumnType::Utf8),
+        required("exchange_id", ColumnType::Utf8),
+        required("trade_time", ColumnType::Timestamp),
+        required("update_time", ColumnType::Timestamp),
+        required("price", ColumnType::Decimal),
+        required("side", ColumnType::Decimal),
+    ];
+
+    fn row(&self) -> Vec<Cell> {
+        vec![
+            Cell::Utf8(self.provider().to_string()),
+            Cell::Utf8(self.symbol().to_string()),
+            Cell::Utf8(self.exchange_id().to_string()),
+            Cell::Timestamp(self.trade_time()),
+            Cell::Timestamp(self.update_time()),
+            Cell::Decimal(self.price()),
+            Cell::Decimal(self.side()),
+        ]
+    }
+}
+
+impl Tabular for OrderResponse {
+    const NAME: &'static str = "order";
+    const COLUMNS: &'static [Column] = &[
+        required("account_id", ColumnType::Utf8),
+        required("venue", ColumnType::Utf8),
+        required("order_id", ColumnType::Utf8),
+        required("symbol", ColumnType::Utf8),
+        required("order_type", ColumnType::Utf8),
+        required("order_side", ColumnType::Utf8),
+        required("order_status", ColumnType::Utf8),
+        optional("limit_price", ColumnType::Decimal),
+        required("quantity", ColumnType::Decimal),
+        required("filled_average_price", ColumnType::Decimal),
+        required("filled_cumulative_quantity", ColumnType::Decimal),
+        required("open_quantity", ColumnType::Decimal),
+        optional("total", ColumnType::Decimal),
+        optional("trigger_price", ColumnType::Decimal),
+        optional("stop_type", ColumnType::Utf8),
+        optional("trigger_time", ColumnType::Timestamp),
+        required("created_at", ColumnType::Timestamp),
+        optional("updated_at", ColumnType::Timestamp),
+        optional("cancelled_updated_at", ColumnType::Timestamp),
+        optional("filled_updated_at", ColumnType::Timestamp),
+    ];
+
+    fn row(&self) -> Vec<Cell> {
+        vec![
+            Cell::Utf8(self.account_id().to_string()),
+            Cell::Utf8(self.venue().to_string()),
+            Cell::Utf8(self.order_id().to_string()),
+            Cell::Utf8(self.symbol().to_string()),
+            Cell::Utf8(self.order_type().to_string()),
+            Cell::Utf8(self.order_side().to_string()),
+            Cell::Utf8(self.order_status().to_string()),
+            Cell::decimal(self.limit_price()),
+            Cell::Decimal(self.quantity()),
+            Cell::Decimal(self.filled_average_price()),
+            Cell::Decimal(self.filled_cumulative_quantity()),
+            Cell::Decimal(self.open_quantity()),
+            Cell::decimal(self.total()),
+            Cell::decimal(self.trigger_price()),
+            Cell::utf8(self.stop_type()),
+            Cell::timestamp(self.trigger_time()),
+            Cell::Timestamp(self.created_at()),
+            Cell::timestamp(self.updated_at()),
+            Cell::timestamp(self.cancelled_updated_at()),
+            Cell::timestamp(self.filled_updated_at()),
+        ]
+    }
+}
+
+impl Tabular for AccountHistory {
+    const NAME: &'static str = "account_history";
+    const COLUMNS: &'static [Column] = &[
+        required("account_id", ColumnType::Utf8),
+        required("log_id", ColumnType::Utf8),
+        required("type", ColumnType::Utf8),
+        required("status", ColumnType::Utf8),
+        required("currency", ColumnType::Utf8),
+        required("coin", ColumnType::Utf8),
+        required("amount", ColumnType::Decimal),
+        required("fee", ColumnType::Decimal),
+        required("target_address", ColumnType::Utf8),
+        required("from_account_id", ColumnType::Utf8),
+        required("to_account_id", ColumnType::Utf8),
+        required("action_type", ColumnType::Utf8),
+        required("action_note", ColumnType::Utf8),
+        required("log_created_at", ColumnType::Timestamp),
+        required("log_updated_at", ColumnType::Timestamp),
+    ];
+
+    fn row(&self) -> Vec<Cell> {
+        vec![
+            Cell::Utf8(self.account_id().to_string()),
+            Cell::Utf8(self.log_id().to_string()),
+            Cell::Utf8(self.kind().to_string()),
+            Cell::Utf8(self.status().to_string()),
+            Cell::Utf8(self.currency().to_string()),
+            Cell::Utf8(self.coin().to_string()),
+            Cell::Decimal(self.amount()),
+            Cell::Decimal(self.fee()),
+            Cell::Utf8(self.target_address().to_string()),
+            Cell::Utf8(self.from_account_id().to_string()),
+            Cell::Utf8(self.to_account_id().to_string()),
+            Cell::Utf8(self.action_type().to_string()),
+            Cell::Utf8(self.action_note().to_string()),
+            Cell::Timestamp(self.log_created_at()),
+            Cell::Timestamp(self.log_updated_at()),
+        ]
+    }
+}
+
+impl Tabular for LedgerEntry {
+    const NAME: &'static str = "ledger";
+    const COLUMNS: &'static [Column] = &[
+        required("time", ColumnType::Timestamp),
+        required("currency", ColumnType::Utf8),
+        required("type", ColumnType::Utf8),
+        required("amount", ColumnType::Decimal),
+        required("fee", ColumnType::Decimal),
+        required("reference", ColumnType::Utf8),
+    ];
+
+    fn row(&self) -> Vec<Cell> {
+        vec![
+            Cell::Timestamp(self.time),
+            Cell::Utf8(self.currency.clone()),
+            Cell::Utf8(self.kind.clone()),
+            Cell::Decimal(self.amount),
+            Cell::Decimal(self.fee),
+            Cell::Utf8(self.reference.clone()),
+        ]
+    }
+}
+
+impl Tabular for RealizedGain {
+    const NAME: &'static str = "realized_gain";
+    const COLUMNS: &'static [Column] = &[
+        required("symbol", ColumnType::Utf8),
+        required("realized_pnl", ColumnType::Decimal),
+        required("fees", ColumnType::Decimal),
+        required("open_quantity", ColumnType::Decimal),
+    ];
+
+    fn row(&self) -> Vec<Cell> {
+        vec![
+            Cell::Utf8(self.symbol.clone()),
+            Cell::Decimal(self.realized_pnl),
+            Cell::Decimal(self.fees),
+            Cell::Decimal(self.open_quantity),
+        ]
+    }
+}
+
+impl Tabular for CurrencySummary {
+    const NAME: &'static str = "currency_summary";
+    const COLUMNS: &'static [Column] = &[
+        required("currency", ColumnType::Utf8),
+        required("deposits", ColumnType::Decimal),
+        required("withdrawals", ColumnType::Decimal),
+        required("transfers_in", ColumnType::Decimal),
+        required("transfers_out", ColumnType::Decimal),
+        required("trades", ColumnType::Decimal),
+        required("fees", ColumnType::Decimal),
+    ];
+
+    fn row(&self) -> Vec<Cell> {
+        vec![
+            Cell::Utf8(self.currency.clone()),
+            Cell::Decimal(self.deposits),
+            Cell::Decimal(self.withdrawals),
+            Cell::Decimal(self.transfers_in),
+            Cell::Decimal(self.transfers_out),
+            Cell::Decimal(self.trades),
+            Cell::Decimal(self.fees),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn kline() -> KLine {
+        serde_json::from_value(json!({
+            "currencyPair": "BTCUSDT",
+            "period": "1m",
+            "open": 1.5,
+            "high": 2.0,
+            "low": 1.0,
+            "close": 1.25,
+            "vol": 10.0,
+            "count": 3,
+            "timestamp": 1584603012164i64,
+            "exchange": "BINANCE",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_write_csv() {
+        let mut out = vec![];
+        write_csv(&mut out, &[kline()]).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "exchange,currency_pair,period,timestamp,open,high,low,close,vol,count\n\
+             BINANCE,BTCUSDT,1m,2020-03-19T07:30:12.164Z,1.50000000,2.00000000,1.00000000,1.25000000,10.00000000,3\n"
+        );
+    }
+
+    #[test]
+    fn test_write_parquet() {
+        use parquet::file::reader::{FileReader, SerializedFileReader};
+
+        let path = std::env::temp_dir().join(format!("apifiny-export-{}", std::process::id()));
+        write_parquet(std::fs::File::create(&path).unwrap(), &[kline(), kline()]).unwrap();
+        let reader = SerializedFileReader::new(std::fs::File::open(&path).unwrap()).unwrap();
+        assert_eq!(reader.metadata().file_metadata().num_rows(), 2);
+        let rows: Vec<String> = reader.into_iter().map(|r| r.unwrap().to_string()).collect();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            rows[0],
+            "{exchange: \"BINANCE\", currency_pair: \"BTCUSDT\", period: \"1m\", \
+             timestamp: 2020-03-19 07:30:12.164 +00:00, open: 1.50000000, high: 2.00000000, \
+             low: 1.00000000, close: 1.25000000, vol: 10.00000000, count: 3}"
+        );
+        assert_eq!(rows[0], rows[1]);
+
+        let mut big = serde_json::to_value(kline()).unwrap();
+        big["vol"] = json!(12345678901.5);
+        let big: KLine = serde_json::from_value(big).unwrap();
+        write_parquet(std::fs::File::create(&path).unwrap(), &[big]).unwrap();
+        let reader = SerializedFileReader::new(std::fs::File::open(&path).unwrap()).unwrap();
+        let row = reader.into_iter().next().unwrap().unwrap().to_string();
+        std::fs::remove_file(&path).unwrap();
+        assert!(row.contains("vol: 12345678901.50000000,"), "{}", row);
+
+        let mut out = vec![];
+        let mut huge = serde_json::to_value(kline()).unwrap();
+        huge["vol"] = json!(1e30);
+        let huge: KLine = serde_json::from_value(huge).unwrap();
+        assert!(matches!(
+            write_parquet(&mut out, &[huge]),
+            Err(Error::OutOfRange(_))
+        ));
+    }
+}
@@ -0,0 +1,161 @@
+use super::{
+    rest_client::{FeeRate, OrderResponse, VenueClient},
+    Error, Result,
+};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Liquidity {
+    // rests on the book
+    Maker,
+    // crosses the spread
+    Taker,
+}
+
+impl Liquidity {
+    // best guess for a past order, taker for market and stop orders and
+    // maker otherwise
+    pub fn of_order(order: &OrderResponse) -> Liquidity {
+        match order.order_type() {
+            "MARKET" | "STOP" => Liquidity::Taker,
+            _ => Liquidity::Maker,
+        }
+    }
+}
+
+// fee rates of one venue and symbol, fees are charged in the quote currency
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FeeSchedule {
+    pub maker: f64,
+    pub taker: f64,
+}
+
+// a non zero `special_rate` is a negotiated rate and replaces both
+impl From<&FeeRate> for FeeSchedule {
+    fn from(rate: &FeeRate) -> FeeSchedule {
+        if rate.special_rate() > 0.0 {
+            return FeeSchedule {
+                maker: rate.special_rate(),
+                taker: rate.special_rate(),
+            };
+        }
+        FeeSchedule {
+            maker: rate.make_fee(),
+            taker: rate.take_fee(),
+        }
+    }
+}
+
+impl FeeSchedule {
+    pub fn rate(&self, liquidity: Liquidity) -> f64 {
+        match liquidity {
+            Liquidity::Maker => self.maker,
+            Liquidity::Taker => self.taker,
+        }
+    }
+
+    // fee of trading `quantity` at `price`
+    pub fn fee(&self, quantity: f64, price: f64, liquidity: Liquidity) -> f64 {
+        quantity * price * self.rate(liquidity)
+    }
+
+    // largest quantity whose cost plus fee fits in `quote_amount`
+    pub fn max_buy_quantity(&self, quote_amount: f64, price: f64, liquidity: Liquidity) -> f64 {
+        if price <= 0.0 {
+            return 0.0;
+        }
+        (quote_amount / (price * (1.0 + self.rate(liquidity)))).max(0.0)
+    }
+
+    // quote received for selling `quantity` at `price`, after the fee
+    pub fn net_proceeds(&self, quantity: f64, price: f64, liquidity: Liquidity) -> f64 {
+        quantity * price * (1.0 - self.rate(liquidity))
+    }
+}
+
+// Fee schedules per venue and symbol, fetched with `get_commission_rate`
+// once and shared by clones.
+#[derive(Debug, Clone, Default)]
+pub struct FeeModel {
+    schedules: Arc<Mutex<HashMap<(String, String), FeeSchedule>>>,
+}
+
+impl FeeModel {
+    pub fn new() -> FeeModel {
+        FeeModel::default()
+    }
+
+    // set a schedule by hand, e.g. for simulations
+    pub fn insert(&self, venue: &str, symbol: &str, schedule: FeeSchedule) {
+        self.schedules
+            .lock()
+            .unwrap()
+            .insert((venue.to_uppercase(), symbol.to_uppercase()), schedule);
+    }
+
+    pub fn cached(&self, venue: &str, symbol: &str) -> Option<FeeSchedule> {
+        self.schedules
+            .lock()
+            .unwrap()
+            .get(&(venue.to_uppercase(), symbol.to_uppercase()))
+            .copied()
+    }
+
+    // schedule of `symbol` on the handle's venue, fetched on first use
+    pub async fn schedule(&self, client: VenueClient<'_>, symbol: &str) -> Result<FeeSchedule> {
+        let venue = client.venue().name;
+        if let Some(schedule) = self.cached(venue, symbol) {
+            return Ok(schedule);
+        }
+
+        let rate = client
+            .get_commission_rate(symbol.to_string())
+            .await?
+            .into_result()?
+            .into_iter()
+            .next()
+            .ok_or(Error::EmptyResult())?;
+        let schedule = FeeSchedule::from(&rate);
+        self.insert(venue, symbol, schedule);
+        Ok(schedule)
+    }
+
+    // fee of a prospective order on the handle's venue
+    pub async fn estimate(
+        &self,
+        client: VenueClient<'_>,
+        symbol: &str,
+        quantity: f64,
+        price: f64,
+        liquidity: Liquidity,
+    ) -> Result<f64> {
+        Ok(self
+            .schedule(client, symbol)
+            .await?
+            .fee(quantity, price, liquidity))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fee_schedule() {
+        let schedule = FeeSchedule {
+            maker: 0.001,
+            taker: 0.002,
+        };
+        assert_eq!(schedule.fee(2.0, 100.0, Liquidity::Taker), 0.4);
+
+        let quantity = schedule.max_buy_quantity(1002.0, 100.0, Liquidity::Taker);
+        assert!((quantity - 10.0).abs() < 1e-9);
+        let cost = quantity * 100.0 + schedule.fee(quantity, 100.0, Liquidity::Taker);
+        assert!((cost - 1002.0).abs() < 1e-9);
+
+        assert!((schedule.net_proceeds(10.0, 100.0, Liquidity::Maker) - 999.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,196 @@
+use super::{
+    rest_client::{
+        ListCompletedOrderParams, ListFilledOrderParams, OrderResponse, VenueClient,
+        MAX_ORDER_LIMIT,
+    },
+    Result,
+};
+use chrono::{DateTime, Duration, Utc};
+use futures::{stream, Stream};
+use std::collections::{HashSet, VecDeque};
+
+// which order list to walk, with the caller's filters
+enum Source {
+    Filled(ListFilledOrderParams),
+    Completed(ListCompletedOrderParams),
+}
+
+impl Source {
+    async fn fetch(
+        &self,
+        client: VenueClient<'_>,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<OrderResponse>> {
+        match self {
+            Source::Filled(params) => {
+                let params = params
+                    .clone()
+                    .start_time(start)
+                    .end_time(end)
+                    .limit(MAX_ORDER_LIMIT);
+                client.list_filled_order(params).await
+            }
+            Source::Completed(params) => {
+                let params = params
+                    .clone()
+                    .start_time(start)
+                    .end_time(end)
+                    .limit(MAX_ORDER_LIMIT);
+                client.list_completed_order(params).await
+            }
+        }
+    }
+
+    // the time the server filters this list on
+    fn time_of(&self, order: &OrderResponse) -> DateTime<Utc> {
+        match self {
+            Source::Filled(_) => order.filled_updated_at().or_else(|| order.updated_at()),
+            Source::Completed(_) => order.updated_at().or_else(|| order.cancelled_updated_at()),
+        }
+        .unwrap_or_else(|| order.created_at())
+    }
+}
+
+// End of the next, older window after fetching `times` from [since, end].
+// A page below `limit` means the window is complete, a full page continues
+// from its oldest record wherever it sits, so pages may come in any order.
+// Orders at that instant are fetched twice and dropped by order id, which
+// the api keeps unique per venue. More than `limit` orders at one instant
+// can't be paged and the excess is skipped.
+fn next_end(
+    times: &[DateTime<Utc>],
+    since: DateTime<Utc>,
+    end: DateTime<Utc>,
+    limit: usize,
+) -> Option<DateTime<Utc>> {
+    if times.len() < limit {
+        return None;
+    }
+    let oldest = times.iter().min()?;
+    // a full page at a single instant would never move the window
+    let next = if *oldest < end {
+        *oldest
+    } else {
+        end - Duration::milliseconds(1)
+    };
+    (next > since).then_some(next)
+}
+
+struct OrderCursor<'a> {
+    client: VenueClient<'a>,
+    source: Source,
+    since: DateTime<Utc>,
+    // end of the window to fetch next, none once `since` is reached
+    end: Option<DateTime<Utc>>,
+    seen: HashSet<String>,
+    orders: VecDeque<OrderResponse>,
+}
+
+impl<'a> OrderCursor<'a> {
+    async fn next(&mut self) -> Option<Result<OrderResponse>> {
+        loop {
+            if let Some(order) = self.orders.pop_front() {
+                return Some(Ok(order));
+            }
+
+            let end = self.end.take()?;
+            let orders = match self.source.fetch(self.client, self.since, end).await {
+                Ok(orders) => orders,
+                // stop after reporting the error
+                Err(e) => return Some(Err(e)),
+            };
+
+            let times: Vec<_> = orders.iter().map(|o| self.source.time_of(o)).collect();
+            self.end = next_end(&times, self.since, end, MAX_ORDER_LIMIT as usize);
+
+            let mut orders: Vec<_> = orders
+                .into_iter()
+                .filter(|o| self.seen.insert(o.order_id().to_string()))
+                .collect();
+            // newest first, like the windows
+            orders.sort_by_key(|o| std::cmp::Reverse(self.source.time_of(o)));
+            self.orders.extend(orders);
+        }
+    }
+
+    fn into_stream(self) -> impl Stream<Item = Result<OrderResponse>> + 'a {
+        stream::unfold(self, |mut cursor| async move {
+            cursor.next().await.map(|item| (item, cursor))
+        })
+    }
+}
+
+impl<'a> VenueClient<'a> {
+    // Every filled order between `since` and `until`, newest first, walking
+    // back past the 1000 row cap. Time and limit of `params` are overridden,
+    // its symbol and order id filters are kept.
+    pub fn filled_order_history(
+        &self,
+        params: ListFilledOrderParams,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> impl Stream<Item = Result<OrderResponse>> + 'a {
+        self.order_history(Source::Filled(params), since, until)
+    }
+
+    // like `filled_order_history`, for filled and cancelled orders
+    pub fn completed_order_history(
+        &self,
+        params: ListCompletedOrderParams,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> impl Stream<Item = Result<OrderResponse>> + 'a {
+        self.order_history(Source::Completed(params), since, until)
+    }
+
+    fn order_history(
+        &self,
+        source: Source,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> impl Stream<Item = Result<OrderResponse>> + 'a {
+        OrderCursor {
+            client: *self,
+            source,
+            since,
+            end: (until > since).then_some(until),
+            seen: HashSet::new(),
+            orders: VecDeque::new(),
+        }
+        .into_stream()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_next_end() {
+        let at = |s| Utc.with_ymd_and_hms(2022, 1, 1, 0, 0, s).unwrap();
+        let since = at(0);
+        let end = at(50);
+
+        // partial page, the window is complete
+        assert_eq!(next_end(&[at(40), at(30)], since, end, 3), None);
+        // full page, continue from the oldest record
+        assert_eq!(
+            next_end(&[at(40), at(30), at(20)], since, end, 3),
+            Some(at(20))
+        );
+        // oldest first pages continue from the same record
+        assert_eq!(
+            next_end(&[at(20), at(30), at(40)], since, end, 3),
+            Some(at(20))
+        );
+        // full page at the window end still moves back
+        assert_eq!(
+            next_end(&[at(50), at(50)], since, end, 2),
+            Some(at(50) - Duration::milliseconds(1))
+        );
+        // reached the start
+        assert_eq!(next_end(&[at(10), at(0)], since, end, 2), None);
+    }
+}
@@ -0,0 +1,220 @@
+use super::{
+    rest_client::{AccountHistory, QueryAccountHistoryParams, VenueClient},
+    Error, Result,
+};
+use chrono::{DateTime, Duration, Utc};
+use futures::{stream, Stream};
+use std::collections::VecDeque;
+
+// only keep records matching every field that is set
+#[derive(Debug, Clone, Default)]
+pub struct HistoryFilter {
+    // DEPOSIT, WITHDRAW, TRANSFER, ...
+    pub kind: Option<String>,
+    pub currency: Option<String>,
+    // SUBMITTED, COMPLETED, CANCELLED
+    pub status: Option<String>,
+}
+
+impl HistoryFilter {
+    pub fn matches(&self, record: &AccountHistory) -> bool {
+        let eq = |want: &Option<String>, got: &str| {
+            want.as_ref()
+                .map(|w| w.eq_ignore_ascii_case(got))
+                .unwrap_or(true)
+        };
+        eq(&self.kind, record.kind())
+            && eq(&self.currency, record.currency())
+            && eq(&self.status, record.status())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HistoryQuery {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    // longer ranges are split into windows of this size, for servers that
+    // cap the range of one query
+    pub window: Duration,
+    // records per page
+    pub limit: i32,
+    pub filter: HistoryFilter,
+}
+
+impl HistoryQuery {
+    pub fn new(start: DateTime<Utc>, end: DateTime<Utc>) -> HistoryQuery {
+        HistoryQuery {
+            start,
+            end,
+            window: Duration::days(30),
+            limit: 100,
+            filter: HistoryFilter::default(),
+        }
+    }
+
+    // Bounds of each window, oldest first. The api includes both bounds,
+    // so every window but the last ends a millisecond before the next one
+    // starts and a record on the boundary is only listed once. Equal start
+    // and end make a single window of that instant.
+    fn windows(&self) -> Result<VecDeque<(DateTime<Utc>, DateTime<Utc>)>> {
+        if self.window < Duration::milliseconds(1) {
+            return Err(Error::InvalidArgument(format!(
+                "history window must be at least a millisecond, got {}",
+                self.window
+            )));
+        }
+        if self.end < self.start {
+            return Err(Error::InvalidArgument(format!(
+                "history ends at {} before it starts at {}",
+                self.end, self.start
+            )));
+        }
+        let mut windows = VecDeque::new();
+        let mut start = self.start;
+        loop {
+            let next = start + self.window;
+            if next >= self.end {
+                windows.push_back((start, self.end));
+                return Ok(windows);
+            }
+            windows.push_back((start, next - Duration::milliseconds(1)));
+            start = next;
+        }
+    }
+}
+
+struct HistoryCursor<'a> {
+    client: VenueClient<'a>,
+    query: HistoryQuery,
+    windows: VecDeque<(DateTime<Utc>, DateTime<Utc>)>,
+    // window and page to fetch next, if the current window has more pages
+    current: Option<(DateTime<Utc>, DateTime<Utc>, i32)>,
+    records: VecDeque<AccountHistory>,
+    // an invalid query, reported as the only item
+    error: Option<Error>,
+}
+
+impl<'a> HistoryCursor<'a> {
+    async fn next(&mut self) -> Option<Result<AccountHistory>> {
+        if let Some(e) = self.error.take() {
+            return Some(Err(e));
+        }
+        loop {
+            if let Some(record) = self.records.pop_front() {
+                return Some(Ok(record));
+            }
+
+            let (start, end, page) = match self.current.take() {
+                Some(current) => current,
+                None => {
+                    let (start, end) = self.windows.pop_front()?;
+                    (start, end, 1)
+                }
+            };
+            let params = QueryAccountHistoryParams {
+                start_time_date: start,
+                end_time_date: end,
+                limit: self.query.limit,
+                page,
+            };
+            let resp = self
+                .client
+                .query_asset_activity_list(params)
+                .await
+                .and_then(|resp| resp.into_result());
+            let resp = match resp {
+                Ok(resp) => resp,
+                Err(e) => {
+                    // stop after reporting the error
+                    self.windows.clear();
+                    return Some(Err(e));
+                }
+            };
+
+            if page < resp.pages() {
+                self.current = Some((start, end, page + 1));
+            }
+            let filter = &self.query.filter;
+            self.records.extend(
+                resp.into_records()
+                    .into_iter()
+                    .filter(|r| filter.matches(r)),
+            );
+        }
+    }
+}
+
+impl<'a> VenueClient<'a> {
+    // every account activity between `start` and `end`, across all pages
+    pub fn account_history_stream(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> impl Stream<Item = Result<AccountHistory>> + 'a {
+        self.account_history_query(HistoryQuery::new(start, end))
+    }
+
+    // like `account_history_stream`, with windowing and filters
+    pub fn account_history_query(
+        &self,
+        query: HistoryQuery,
+    ) -> impl Stream<Item = Result<AccountHistory>> + 'a {
+        let (windows, error) = match query.windows() {
+            Ok(windows) => (windows, None),
+            Err(e) => (VecDeque::new(), Some(e)),
+        };
+        let cursor = HistoryCursor {
+            client: *self,
+            windows,
+            query,
+            current: None,
+            records: VecDeque::new(),
+            error,
+        };
+        stream::unfold(cursor, |mut cursor| async move {
+            cursor.next().await.map(|item| (item, cursor))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_windows() {
+        let start = Utc.with_ymd_and_hms(2022, 1, 1, 0, 0, 0).unwrap();
+        let mut query = HistoryQuery::new(start, start + Duration::days(10));
+        query.window = Duration::days(4);
+
+        let ms = |t: DateTime<Utc>| (t - start).num_milliseconds();
+        let day = Duration::days(1).num_milliseconds();
+        let windows: Vec<_> = query
+            .windows()
+            .unwrap()
+            .into_iter()
+            .map(|(s, e)| (ms(s), ms(e)))
+            .collect();
+        assert_eq!(
+            windows,
+            vec![
+                (0, 4 * day - 1),
+                (4 * day, 8 * day - 1),
+                (8 * day, 10 * day)
+            ]
+        );
+
+        // both bounds are included, a single instant is one window
+        query.end = start;
+        assert_eq!(query.windows().unwrap(), vec![(start, start)]);
+        query.end = start - Duration::milliseconds(1);
+        assert!(query.windows().is_err());
+
+        query.end = start + Duration::days(10);
+        query.window = Duration::zero();
+        assert!(query.windows().is_err());
+        query.window = Duration::days(-1);
+        assert!(query.windows().is_err());
+    }
+}
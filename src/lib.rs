@@ -1,13 +1,78 @@
 mod config;
 pub use config::ApiFiny;
 
+mod secret;
+pub use secret::Secret;
+
+/// credential sources
+pub mod credentials;
+
 mod token;
+pub use token::Signer;
 
 mod utils;
 
+/// runtime venue registry
+pub mod venues;
+
+/// server clock synchronization
+pub mod clock;
+
 /// rest client
 pub mod rest_client;
-pub use rest_client::{Period, RestClient};
+pub use rest_client::{Period, RestClient, VenueClient};
+
+mod rate_limit;
+pub use rate_limit::RateLimiter;
+
+// polling market data streams
+mod stream;
+
+mod symbol;
+pub use symbol::{Coin, CurrencyTable, Symbol, SymbolTable};
+
+/// multiple accounts and sub accounts
+pub mod accounts;
+
+/// paginated account history
+pub mod history;
+
+/// account activity status tracking
+pub mod tracker;
+
+/// guided withdrawals
+pub mod withdraw;
+
+/// withdraw allowlist and approvals
+pub mod treasury;
+
+/// cross venue balances
+pub mod portfolio;
+
+/// inventory rebalancing between venues
+pub mod rebalance;
+
+/// multi venue ticker aggregation, paced by the client's rate limiter
+pub mod aggregate;
+
+/// complete order and fill history
+pub mod fills;
+
+/// trading fee estimates
+pub mod fee;
+
+/// positions and pnl from fills
+pub mod position;
+
+/// ledgers and realized gains reports
+pub mod report;
+
+/// market data recording and replay
+pub mod recorder;
+
+/// csv and parquet export
+#[cfg(feature = "export")]
+pub mod export;
 
 mod constants;
 pub use constants::*;
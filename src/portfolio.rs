@@ -0,0 +1,171 @@
+use super::{
+    rest_client::{Balance, VenueClient},
+    Error, RestClient, Result, Symbol,
+};
+use futures::future::join_all;
+use std::collections::BTreeMap;
+
+// one currency across every venue
+#[derive(Debug, Clone, Default)]
+pub struct Holding {
+    pub currency: String,
+    pub amount: f64,
+    pub available: f64,
+    pub frozen: f64,
+    // amount held per venue
+    pub venues: BTreeMap<String, f64>,
+    // price in the portfolio's quote currency, none if no ticker was found
+    pub price: Option<f64>,
+}
+
+impl Holding {
+    pub fn value(&self) -> Option<f64> {
+        self.price.map(|p| p * self.amount)
+    }
+}
+
+#[derive(Debug)]
+pub struct Portfolio {
+    pub quote: String,
+    pub holdings: Vec<Holding>,
+    // venues whose balances could not be fetched
+    pub failures: Vec<(String, Error)>,
+}
+
+impl Portfolio {
+    // value of every holding that has a price, in `quote`
+    pub fn total_value(&self) -> f64 {
+        self.holdings.iter().filter_map(Holding::value).sum()
+    }
+
+    pub fn holding(&self, currency: &str) -> Option<&Holding> {
+        self.holdings
+            .iter()
+            .find(|h| h.currency.eq_ignore_ascii_case(currency))
+    }
+
+    // merge per venue balances into one holding per currency
+    pub fn from_balances(quote: &str, balances: &[Balance]) -> Portfolio {
+        let mut holdings: BTreeMap<String, Holding> = BTreeMap::new();
+        for balance in balances {
+            let holding = holdings
+                .entry(balance.currency().to_uppercase())
+                .or_insert_with(|| Holding {
+                    currency: balance.currency().to_uppercase(),
+                    ..Default::default()
+                });
+            holding.amount += balance.amount();
+            holding.available += balance.available();
+            holding.frozen += balance.frozen();
+            *holding
+                .venues
+                .entry(balance.venue().to_string())
+                .or_default() += balance.amount();
+        }
+
+        Portfolio {
+            quote: quote.to_uppercase(),
+            holdings: holdings.into_values().collect(),
+            failures: vec![],
+        }
+    }
+}
+
+impl<'a> VenueClient<'a> {
+    // Balances of every venue sub account, merged per currency and valued in
+    // `quote` with ticker prices. The handle's venue is used to look up the
+    // sub accounts.
+    pub async fn portfolio(&self, quote: &str) -> Result<Portfolio> {
+        let account = self.query_account_info().await?.into_result()?;
+
+        let results = join_all(account.sub_account_info().iter().map(|sub| async move {
+            let venue = sub.venue().to_string();
+            let balances = async {
+                let v = self.client().venues().get(&venue)?;
+                self.with_venue(v).list_balance().await?.into_result()
+            }
+            .await;
+            (venue, balances)
+        }))
+        .await;
+
+        let mut balances = vec![];
+        let mut failures = vec![];
+        for (venue, result) in results {
+            match result {
+                Ok(b) => balances.extend(b),
+                Err(e) => failures.push((venue, e)),
+            }
+        }
+
+        let mut portfolio = Portfolio::from_balances(quote, &balances);
+        portfolio.failures = failures;
+        self.client().price_holdings(&mut portfolio).await;
+        Ok(portfolio)
+    }
+}
+
+impl RestClient {
+    // price each holding with the ticker of the first venue that lists it
+    async fn price_holdings(&self, portfolio: &mut Portfolio) {
+        let quote = portfolio.quote.clone();
+        let prices = join_all(portfolio.holdings.iter().map(|holding| {
+            let quote = quote.clone();
+            async move {
+                if holding.currency == quote {
+                    return Some(1.0);
+                }
+                let symbol = Symbol::new(&holding.currency, &quote).to_string();
+                for venue in holding.venues.keys() {
+                    if let Ok(ticker) = self.ticker(&symbol, venue).await {
+                        return Some(ticker.close());
+                    }
+                }
+                None
+            }
+        }))
+        .await;
+
+        for (holding, price) in portfolio.holdings.iter_mut().zip(prices) {
+            holding.price = price;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn balance(venue: &str, currency: &str, amount: f64, frozen: f64) -> Balance {
+        serde_json::from_value(json!({
+            "accountId": "STA-1",
+            "venue": venue,
+            "currency": currency,
+            "amount": amount,
+            "available": amount - frozen,
+            "frozen": frozen,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_from_balances() {
+        let mut portfolio = Portfolio::from_balances(
+            "usd",
+            &[
+                balance("BINANCE", "BTC", 1.0, 0.5),
+                balance("HUOBI", "btc", 2.0, 0.0),
+                balance("HUOBI", "USD", 100.0, 0.0),
+            ],
+        );
+        let btc = portfolio.holding("BTC").unwrap();
+        assert_eq!(btc.amount, 3.0);
+        assert_eq!(btc.frozen, 0.5);
+        assert_eq!(btc.venues.len(), 2);
+
+        portfolio.holdings[0].price = Some(10.0);
+        portfolio.holdings[1].price = Some(1.0);
+        assert_eq!(portfolio.total_value(), 130.0);
+    }
+}
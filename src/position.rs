@@ -0,0 +1,383 @@
+use super::{
+    fee::{FeeModel, FeeSchedule, Liquidity},
+    rest_client::{OrderResponse, VenueClient},
+    RestClient, Result,
+};
+use chrono::{DateTime, Utc};
+use futures::TryStreamExt;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+// rounding residue left by closing lots, not worth a lot of its own
+const DUST: f64 = 1e-12;
+
+// one execution, quantity is always positive
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fill {
+    pub order_id: String,
+    pub symbol: String,
+    // BUY or SELL
+    pub side: String,
+    pub quantity: f64,
+    pub price: f64,
+    // in the quote currency
+    pub fee: f64,
+    pub time: DateTime<Utc>,
+}
+
+impl Fill {
+    // signed quantity, negative for sells
+    fn signed_quantity(&self) -> f64 {
+        if self.side.eq_ignore_ascii_case("SELL") {
+            -self.quantity
+        } else {
+            self.quantity
+        }
+    }
+}
+
+// an order's whole filled quantity as one fill, without fees
+impl From<&OrderResponse> for Fill {
+    fn from(order: &OrderResponse) -> Fill {
+        Fill {
+            order_id: order.order_id().to_string(),
+            symbol: order.symbol().to_string(),
+            side: order.order_side().to_string(),
+            quantity: order.filled_cumulative_quantity(),
+            price: order.filled_average_price(),
+            fee: 0.0,
+            time: order
+                .filled_updated_at()
+                .or_else(|| order.updated_at())
+                .unwrap_or_else(|| order.created_at()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum CostMethod {
+    // closes the oldest lots first
+    Fifo,
+    // closes the newest lots first
+    Lifo,
+    // one lot at the average entry price
+    AverageCost,
+}
+
+// open quantity at one entry price, negative for shorts
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Lot {
+    quantity: f64,
+    price: f64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Position {
+    pub symbol: String,
+    // negative for shorts
+    pub quantity: f64,
+    // closed pnl minus every fee paid, in the quote currency
+    pub realized_pnl: f64,
+    pub fees: f64,
+    lots: VecDeque<Lot>,
+}
+
+impl Position {
+    // entry price of the open quantity, none when flat
+    pub fn average_cost(&self) -> Option<f64> {
+        if self.quantity == 0.0 {
+            return None;
+        }
+        let cost: f64 = self.lots.iter().map(|l| l.quantity * l.price).sum();
+        Some(cost / self.quantity)
+    }
+
+    pub fn unrealized_pnl(&self, mark: f64) -> f64 {
+        self.lots
+            .iter()
+            .map(|l| l.quantity * (mark - l.price))
+            .sum()
+    }
+
+    fn apply(&mut self, fill: &Fill, method: CostMethod) {
+        let mut remaining = fill.signed_quantity();
+        self.fees += fill.fee;
+        self.realized_pnl -= fill.fee;
+
+        // close lots on the other side first
+        while remaining.abs() > DUST {
+            let lot = match method {
+                CostMethod::Lifo => self.lots.back_mut(),
+                _ => self.lots.front_mut(),
+            };
+            let lot = match lot {
+                Some(lot) if lot.quantity.signum() != remaining.signum() => lot,
+                _ => break,
+            };
+            let closed = if lot.quantity.abs() <= remaining.abs() {
+                -lot.quantity
+            } else {
+                remaining
+            };
+            // closing a long sells at the fill price, closing a short buys
+            self.realized_pnl += -closed * (fill.price - lot.price);
+            lot.quantity += closed;
+            remaining -= closed;
+            if lot.quantity == 0.0 {
+                match method {
+                    CostMethod::Lifo => self.lots.pop_back(),
+                    _ => self.lots.pop_front(),
+                };
+            }
+        }
+
+        if remaining.abs() > DUST {
+            self.lots.push_back(Lot {
+                quantity: remaining,
+                price: fill.price,
+            });
+        }
+        self.quantity = self.lots.iter().map(|l| l.quantity).sum();
+
+        if method == CostMethod::AverageCost && self.lots.len() > 1 {
+            let price = self.average_cost().unwrap_or(0.0);
+            self.lots.clear();
+            self.lots.push_back(Lot {
+                quantity: self.quantity,
+                price,
+            });
+        }
+    }
+}
+
+// what unrealized pnl is marked against
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mark {
+    // ticker close
+    Last,
+    // middle of the best bid and ask
+    Mid,
+}
+
+// per symbol positions built from fills
+#[derive(Debug, Clone)]
+pub struct PositionBook {
+    method: CostMethod,
+    positions: BTreeMap<String, Position>,
+    // filled quantity and average price already applied per order, so
+    // repeated updates of a partially filled order only apply the new part
+    applied: HashMap<String, (f64, f64)>,
+}
+
+impl PositionBook {
+    pub fn new(method: CostMethod) -> PositionBook {
+        PositionBook {
+            method,
+            positions: BTreeMap::new(),
+            applied: HashMap::new(),
+        }
+    }
+
+    pub fn method(&self) -> CostMethod {
+        self.method
+    }
+
+    pub fn position(&self, symbol: &str) -> Option<&Position> {
+        self.positions.get(symbol)
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = &Position> {
+        self.positions.values()
+    }
+
+    // realized pnl of every symbol, net of fees
+    pub fn realized_pnl(&self) -> f64 {
+        self.positions.values().map(|p| p.realized_pnl).sum()
+    }
+
+    // apply one execution
+    pub fn apply(&mut self, fill: &Fill) {
+        if fill.quantity <= 0.0 {
+            return;
+        }
+        let method = self.method;
+        self.positions
+            .entry(fill.symbol.clone())
+            .or_insert_with(|| Position {
+                symbol: fill.symbol.clone(),
+                ..Default::default()
+            })
+            .apply(fill, method);
+    }
+
+    // Apply an order from `list_filled_order` or a live update. Only the
+    // quantity filled since the last update of the same order is applied.
+    pub fn apply_order(&mut self, order: &OrderResponse) {
+        if let Some(fill) = self.new_fill(order) {
+            self.apply(&fill);
+        }
+    }
+
+    // like `apply_order`, charging the new part with `schedule`
+    pub fn apply_order_with_fees(
+        &mut self,
+        order: &OrderResponse,
+        schedule: &FeeSchedule,
+        liquidity: Liquidity,
+    ) {
+        if let Some(mut fill) = self.new_fill(order) {
+            fill.fee = schedule.fee(fill.quantity, fill.price, liquidity);
+            self.apply(&fill);
+        }
+    }
+
+    // the part of `order` filled since its last update, if any
+    fn new_fill(&mut self, order: &OrderResponse) -> Option<Fill> {
+        let mut fill = Fill::from(order);
+        let (quantity, price) = self
+            .applied
+            .get(&fill.order_id)
+            .copied()
+            .unwrap_or((0.0, 0.0));
+        if fill.quantity <= quantity {
+            return None;
+        }
+        self.applied
+            .insert(fill.order_id.clone(), (fill.quantity, fill.price));
+
+        // price of the new part from the change in average price
+        let cost = fill.quantity * fill.price - quantity * price;
+        fill.quantity -= quantity;
+        fill.price = cost / fill.quantity;
+        Some(fill)
+    }
+
+    // drop every position and replay `fills` oldest first
+    pub fn rebuild<'f>(&mut self, fills: impl IntoIterator<Item = &'f Fill>) {
+        self.positions.clear();
+        self.applied.clear();
+        let mut fills: Vec<_> = fills.into_iter().collect();
+        fills.sort_by_key(|f| f.time);
+        for fill in fills {
+            self.apply(fill);
+        }
+    }
+
+    // Unrealized pnl per symbol, marked with `venue` market data. Symbols
+    // whose market data could not be fetched are left out.
+    pub async fn unrealized_pnl(
+        &self,
+        client: &RestClient,
+        venue: &str,
+        mark: Mark,
+    ) -> BTreeMap<String, f64> {
+        let mut pnl = BTreeMap::new();
+        for position in self.positions.values().filter(|p| p.quantity != 0.0) {
+            match client.mark_price(&position.symbol, venue, mark).await {
+                Ok(price) => {
+                    pnl.insert(position.symbol.clone(), position.unrealized_pnl(price));
+                }
+                Err(e) => tracing::warn!("mark {} failed: {}", position.symbol, e),
+            }
+        }
+        pnl
+    }
+}
+
+impl RestClient {
+    pub async fn mark_price(&self, symbol: &str, venue: &str, mark: Mark) -> Result<f64> {
+        match mark {
+            Mark::Last => Ok(self.ticker(symbol, venue).await?.close()),
+            Mark::Mid => {
+                let book = self.order_book(symbol, venue).await?;
+                let bid = book.bids().iter().map(|b| b.price()).reduce(f64::max);
+                let ask = book.asks().iter().map(|a| a.price()).reduce(f64::min);
+                match (bid, ask) {
+                    (Some(bid), Some(ask)) => Ok((bid + ask) / 2.0),
+                    _ => Err(super::Error::EmptyResult()),
+                }
+            }
+        }
+    }
+}
+
+impl<'a> VenueClient<'a> {
+    // A position book rebuilt from every filled order since `since`. With a
+    // fee model, fees are estimated, the order list carries no fee.
+    pub async fn rebuild_positions(
+        &self,
+        method: CostMethod,
+        since: DateTime<Utc>,
+        fees: Option<&FeeModel>,
+    ) -> Result<PositionBook> {
+        let mut orders: Vec<OrderResponse> = self
+            .filled_order_history(Default::default(), since, self.client().clock().now())
+            .try_collect()
+            .await?;
+        orders.sort_by_key(|o| Fill::from(o).time);
+
+        // through `apply_order`, so later live updates of these orders are
+        // not applied twice
+        let mut book = PositionBook::new(method);
+        for order in &orders {
+            match fees {
+                Some(fees) => {
+                    let schedule = fees.schedule(*self, order.symbol()).await?;
+                    book.apply_order_with_fees(order, &schedule, Liquidity::of_order(order));
+                }
+                None => book.apply_order(order),
+            }
+        }
+        Ok(book)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn fill(side: &str, quantity: f64, price: f64, fee: f64) -> Fill {
+        Fill {
+            order_id: String::new(),
+            symbol: "BTCUSDT".to_string(),
+            side: side.to_string(),
+            quantity,
+            price,
+            fee,
+            time: Utc.with_ymd_and_hms(2022, 1, 1, 0, 0, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_cost_methods() {
+        let fills = [
+            fill("BUY", 1.0, 100.0, 1.0),
+            fill("BUY", 1.0, 200.0, 1.0),
+            fill("SELL", 1.5, 300.0, 1.0),
+        ];
+
+        let mut fifo = PositionBook::new(CostMethod::Fifo);
+        fifo.rebuild(&fills);
+        let p = fifo.position("BTCUSDT").unwrap();
+        assert_eq!(p.quantity, 0.5);
+        // 200 + 50 closed, minus fees
+        assert_eq!(p.realized_pnl, 247.0);
+        assert_eq!(p.average_cost(), Some(200.0));
+        assert_eq!(p.unrealized_pnl(250.0), 25.0);
+
+        let mut avg = PositionBook::new(CostMethod::AverageCost);
+        avg.rebuild(&fills);
+        let p = avg.position("BTCUSDT").unwrap();
+        assert_eq!(p.realized_pnl, 222.0);
+        assert_eq!(p.average_cost(), Some(150.0));
+
+        // flip to short and cover
+        avg.apply(&fill("SELL", 1.0, 200.0, 0.0));
+        avg.apply(&fill("BUY", 0.5, 100.0, 0.0));
+        let p = avg.position("BTCUSDT").unwrap();
+        assert_eq!(p.quantity, 0.0);
+        assert_eq!(p.realized_pnl, 222.0 + 25.0 + 50.0);
+    }
+}
@@ -0,0 +1,46 @@
+use std::{sync::Arc, time::Duration};
+use tokio::{
+    sync::Mutex,
+    time::{sleep_until, Instant},
+};
+
+// spaces requests at least `interval` apart, clones share the same budget
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    interval: Duration,
+    next: Arc<Mutex<Option<Instant>>>,
+}
+
+impl RateLimiter {
+    pub fn new(interval: Duration) -> RateLimiter {
+        RateLimiter {
+            interval,
+            next: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    pub fn per_second(requests: u32) -> RateLimiter {
+        RateLimiter::new(Duration::from_secs(1) / requests.max(1))
+    }
+
+    // wait until the next request is allowed
+    pub async fn acquire(&self) {
+        let mut next = self.next.lock().await;
+        let now = Instant::now();
+        let at = match *next {
+            Some(at) if at > now => {
+                sleep_until(at).await;
+                at
+            }
+            _ => now,
+        };
+        *next = Some(at + self.interval);
+    }
+}
+
+impl Default for RateLimiter {
+    // public endpoints are throttled by IP address, 1 request per second
+    fn default() -> Self {
+        RateLimiter::per_second(1)
+    }
+}
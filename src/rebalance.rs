@@ -0,0 +1,582 @@
+use super::{
+    rest_client::{
+        AccountHistory, TransferBetweenVenuesParams, TransferBetweenVenuesResponse, VenueClient,
+    },
+    tracker::{ActivityTracker, TrackerConfig},
+    Error, RestClient, Result,
+};
+use futures::future::join_all;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::{self, Display, Formatter},
+};
+
+// target share of each venue per currency, e.g. {"USDT": {"BINANCE": 0.6, "HUOBI": 0.4}},
+// shares are normalized, venues without a share are left alone
+pub type Targets = HashMap<String, HashMap<String, f64>>;
+
+// one planned move between venues
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transfer {
+    pub currency: String,
+    pub from: String,
+    pub to: String,
+    pub amount: f64,
+    // within the source venue's instant transfer quota
+    pub instant: bool,
+    // estimated fee, in `currency`
+    pub fee: f64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Plan {
+    pub transfers: Vec<Transfer>,
+    // remainders past the instant quota too small to send, not executed
+    pub skipped: Vec<Transfer>,
+}
+
+// dry-run output, one transfer per line
+impl Display for Plan {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if self.transfers.is_empty() && self.skipped.is_empty() {
+            return writeln!(f, "balanced, nothing to transfer");
+        }
+        for t in &self.transfers {
+            writeln!(
+                f,
+                "{} {} {} -> {} fee {} {}",
+                t.amount,
+                t.currency,
+                t.from,
+                t.to,
+                t.fee,
+                if t.instant { "instant" } else { "regular" }
+            )?;
+        }
+        for t in &self.skipped {
+            writeln!(
+                f,
+                "{} {} {} -> {} skipped, below the minimum transfer",
+                t.amount, t.currency, t.from, t.to
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// a transfer of the plan and its final account activity, or why it failed
+#[derive(Debug)]
+pub struct ExecutedTransfer {
+    pub transfer: Transfer,
+    // none if the transfer could not be submitted
+    pub response: Option<TransferBetweenVenuesResponse>,
+    pub result: Result<AccountHistory>,
+}
+
+pub struct Rebalancer<'a> {
+    client: &'a RestClient,
+    targets: Targets,
+    // smaller moves are not worth the fees
+    min_transfer: f64,
+    // status tracking while executing
+    tracker: TrackerConfig,
+}
+
+impl<'a> Rebalancer<'a> {
+    pub fn new(client: &'a RestClient, targets: Targets) -> Rebalancer<'a> {
+        Rebalancer {
+            client,
+            targets,
+            min_transfer: 0.0,
+            tracker: TrackerConfig::default(),
+        }
+    }
+
+    pub fn min_transfer(mut self, amount: f64) -> Rebalancer<'a> {
+        self.min_transfer = amount;
+        self
+    }
+
+    pub fn tracker(mut self, config: TrackerConfig) -> Rebalancer<'a> {
+        self.tracker = config;
+        self
+    }
+
+    // compare balances with the targets and plan transfers, nothing is sent
+    pub async fn plan(&self) -> Result<Plan> {
+        let mut plan = Plan::default();
+        for (currency, shares) in &self.targets {
+            let mut available = BTreeMap::new();
+            for venue in shares.keys() {
+                let client = self.venue_client(venue)?;
+                let balances = client.list_balance().await?.into_result()?;
+                let amount = balances
+                    .iter()
+                    .filter(|b| b.currency().eq_ignore_ascii_case(currency))
+                    .map(|b| b.available())
+                    .sum::<f64>();
+                available.insert(venue.clone(), amount);
+            }
+
+            // instant quota left per source venue, shared by its moves
+            let mut quotas = HashMap::new();
+            for (from, to, amount) in plan_moves(&available, shares, self.min_transfer) {
+                self.price_move(&mut plan, &mut quotas, currency, &from, &to, amount)
+                    .await?;
+            }
+        }
+        Ok(plan)
+    }
+
+    // Send every transfer of `plan` and follow each one until it is
+    // COMPLETED or CANCELLED. A failed submission is recorded in its
+    // `ExecutedTransfer` and the rest of the plan still goes out.
+    pub async fn execute(&self, plan: &Plan) -> Vec<ExecutedTransfer> {
+        let mut submitted = vec![];
+        for transfer in &plan.transfers {
+            let response = self.submit(transfer).await;
+            match &response {
+                Ok((_, r)) => tracing::info!(
+                    "transfer {} {} {} -> {} submitted, log id {}",
+                    transfer.amount,
+                    transfer.currency,
+                    transfer.from,
+                    transfer.to,
+                    r.log_id()
+                ),
+                Err(e) => tracing::warn!(
+                    "transfer {} {} {} -> {} failed: {}",
+                    transfer.amount,
+                    transfer.currency,
+                    transfer.from,
+                    transfer.to,
+                    e
+                ),
+            }
+            submitted.push((transfer.clone(), response));
+        }
+
+        // activities are listed by the source venue, one tracker per venue
+        let mut by_venue: BTreeMap<&str, (VenueClient, Vec<_>)> = BTreeMap::new();
+        for (transfer, response) in &submitted {
+            if let Ok((client, r)) = response {
+                by_venue
+                    .entry(transfer.from.as_str())
+                    .or_insert_with(|| (*client, vec![]))
+                    .1
+                    .push((r.log_id().to_string(), r.log_created_at()));
+            }
+        }
+        let tracked = join_all(by_venue.values().map(|(client, log_ids)| {
+            let tracker = ActivityTracker::new(*client, self.tracker.clone());
+            async move { tracker.track_many(log_ids).await }
+        }))
+        .await;
+        let mut results: HashMap<_, _> = tracked.into_iter().flatten().collect();
+
+        submitted
+            .into_iter()
+            .map(|(transfer, response)| match response {
+                Ok((_, response)) => ExecutedTransfer {
+                    transfer,
+                    result: results
+                        .remove(response.log_id())
+                        .unwrap_or_else(|| Err(Error::EmptyResult())),
+                    response: Some(response),
+                },
+                Err(e) => ExecutedTransfer {
+                    transfer,
+                    response: None,
+                    result: Err(e),
+                },
+            })
+            .collect()
+    }
+
+    async fn submit(
+        &self,
+        transfer: &Transfer,
+    ) -> Result<(VenueClient<'a>, TransferBetweenVenuesResponse)> {
+        let client = self.venue_client(&transfer.from)?;
+        let params = TransferBetweenVenuesParams {
+            currency: transfer.currency.clone(),
+            amount: transfer.amount,
+            target_venue: transfer.to.clone(),
+        };
+        let response = client
+            .transfer_to_venue(params)
+            .await?
+            .into_result()?
+            .into_iter()
+            .next()
+            .ok_or(Error::EmptyResult())?;
+        Ok((client, response))
+    }
+
+    fn venue_client(&self, venue: &str) -> Result<VenueClient<'a>> {
+        self.client.venue_named(venue)
+    }
+
+    // Split a move into an instant part within the source venue's quota and
+    // a regular part for the rest, with their fees. Venues without instant
+    // transfers only get the regular part. `quotas` holds what is left of
+    // each venue's quota in `currency`, fetched on its first move.
+    async fn price_move(
+        &self,
+        plan: &mut Plan,
+        quotas: &mut HashMap<String, f64>,
+        currency: &str,
+        from: &str,
+        to: &str,
+        amount: f64,
+    ) -> Result<()> {
+        let client = self.venue_client(from)?;
+        let quota = match quotas.get(from) {
+            Some(quota) => *quota,
+            None if self.client.venues().supports_instant(from) => client
+                .query_max_instant_amount(currency.to_string())
+                .await?
+                .into_result()?,
+            None => 0.0,
+        };
+        let info = client
+            .client()
+            .list_currency(from)
+            .await?
+            .into_result()?
+            .into_iter()
+            .find(|c| c.currency().eq_ignore_ascii_case(currency));
+        let instant_rate = info
+            .as_ref()
+            .and_then(|c| c.instant_fee_rate().parse::<f64>().ok())
+            .unwrap_or(0.0);
+        // A regular transfer leaves the source venue as a withdrawal, its
+        // response reports the withdrawal fee. The api quotes no other fee
+        // for it, so the venue's minimum withdrawal fee is the estimate.
+        let regular_fee = info.as_ref().map(|c| c.withdraw_min_fee()).unwrap_or(0.0);
+
+        let transfer = |amount: f64, instant: bool| Transfer {
+            currency: currency.to_string(),
+            from: from.to_string(),
+            to: to.to_string(),
+            amount,
+            instant,
+            fee: if instant {
+                amount * instant_rate
+            } else {
+                regular_fee
+            },
+        };
+
+        let (instant, regular, rest) = split_move(amount, quota, self.min_transfer);
+        quotas.insert(from.to_string(), (quota - instant).max(0.0));
+        if instant > 0.0 {
+            plan.transfers.push(transfer(instant, true));
+        }
+        if regular > 0.0 {
+            plan.transfers.push(transfer(regular, false));
+        }
+        if rest > 0.0 {
+            tracing::warn!(
+                "{} {} {} -> {} past the instant quota is below the minimum transfer, skipped",
+                rest,
+                currency,
+                from,
+                to
+            );
+            plan.skipped.push(transfer(rest, false));
+        }
+        Ok(())
+    }
+}
+
+// Instant and regular parts of a move of `amount` with `quota` available
+// for instant transfers. The part past the quota is only sent if it is
+// above `min_transfer`, otherwise it is returned as the rest.
+// Returns (instant, regular, rest).
+pub fn split_move(amount: f64, quota: f64, min_transfer: f64) -> (f64, f64, f64) {
+    let instant = amount.min(quota.max(0.0));
+    let remainder = amount - instant;
+    if remainder > min_transfer {
+        (instant, remainder, 0.0)
+    } else {
+        (instant, 0.0, remainder)
+    }
+}
+
+// Moves that bring `available` to the normalized `shares`, largest surplus
+// to largest deficit first. Returns (from, to, amount).
+pub fn plan_moves(
+    available: &BTreeMap<String, f64>,
+    shares: &HashMap<String, f64>,
+    min_transfer: f64,
+) -> Vec<(String, String, f64)> {
+    let total: f64 = available.values().sum();
+    let weight: f64 = shares.values().filter(|w| **w > 0.0).sum();
+    if total <= 0.0 || weight <= 0.0 {
+        return vec![];
+    }
+
+    let mut surplus = vec![];
+    let mut deficit = vec![];
+    for (venue, amount) in available {
+        let share = shares.get(venue).copied().unwrap_or(0.0).max(0.0);
+        let diff = amount - total * share / weight;
+        if diff > 0.0 {
+            surplus.push((venue.clone(), diff));
+        } else if diff < 0.0 {
+            deficit.push((venue.clone(), -diff));
+        }
+    }
+    surplus.sort_by(|a, b| b.1.total_cmp(&a.1));
+    deficit.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    let mut moves = vec![];
+    let (mut i, mut j) = (0, 0);
+    while i < surplus.len() && j < deficit.len() {
+        let amount = surplus[i].1.min(deficit[j].1);
+        if amount > min_transfer {
+            moves.push((surplus[i].0.clone(), deficit[j].0.clone(), amount));
+        }
+        surplus[i].1 -= amount;
+        deficit[j].1 -= amount;
+        if surplus[i].1 <= min_transfer {
+            i += 1;
+        }
+        if deficit[j].1 <= min_transfer {
+            j += 1;
+        }
+    }
+    moves
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        venues::{VenueRegistry, VenueUrls},
+        ApiFiny,
+    };
+    use serde_json::{json, Value};
+    use std::time::Duration;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    // answers each request with the json `route` returns for its path
+    async fn serve(route: fn(&str) -> Value) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                tokio::spawn(async move {
+                    let mut buf = vec![0; 8192];
+                    let n = stream.read(&mut buf).await.unwrap();
+                    let request = String::from_utf8_lossy(&buf[..n]);
+                    let path = request.split(' ').nth(1).unwrap_or_default();
+                    let path = path.split('?').next().unwrap_or_default();
+                    let body = route(path).to_string();
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    );
+                    stream.write_all(response.as_bytes()).await.unwrap();
+                });
+            }
+        });
+        format!("http://{}", addr)
+    }
+
+    fn transfer(from: &str, to: &str, amount: f64) -> Transfer {
+        Transfer {
+            currency: "USDT".to_string(),
+            from: from.to_string(),
+            to: to.to_string(),
+            amount,
+            instant: false,
+            fee: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_split_move() {
+        assert_eq!(split_move(100.0, 0.0, 1.0), (0.0, 100.0, 0.0));
+        assert_eq!(split_move(100.0, 60.0, 1.0), (60.0, 40.0, 0.0));
+        assert_eq!(split_move(100.0, 99.5, 1.0), (99.5, 0.0, 0.5));
+        assert_eq!(split_move(100.0, 500.0, 1.0), (100.0, 0.0, 0.0));
+        assert_eq!(split_move(100.0, -1.0, 1.0), (0.0, 100.0, 0.0));
+    }
+
+    #[tokio::test]
+    async fn test_plan() {
+        let url = serve(|path| {
+            let balance = |venue: &str, amount: f64| {
+                json!({"result": [{
+                    "accountId": "STA-1", "venue": venue, "currency": "USDT",
+                    "amount": amount, "available": amount, "frozen": 0.0,
+                }]})
+            };
+            match path {
+                "/bn/asset/listBalance" => balance("BINANCE", 900.0),
+                "/hb/asset/listBalance" => balance("HUOBI", 100.0),
+                "/ok/asset/listBalance" => balance("OKEX", 0.0),
+                // one quota for both moves out of BINANCE
+                "/bn/asset/query-max-instant-amount" => json!({"result": 450.0}),
+                "/ac/v2/BINANCE/utils/listCurrency" => json!({"result": [{
+                    "currency": "USDT", "currencyPrecision": 6, "status": "DEPOSIT_WITHDRAW",
+                    "withdrawMaxAmount": 0.0, "withdrawMinAmount": 10.0, "withdrawMinFee": 1.0,
+                    "instantFeeRate": "0.001", "coin": "USDT",
+                }]}),
+                _ => json!({"error": {"code": 404, "message": path}}),
+            }
+        })
+        .await;
+
+        let client = RestClient::new(ApiFiny::new("key-1", "secret", "STA-1"))
+            .unwrap()
+            .with_venue_registry(VenueRegistry::new(VenueUrls {
+                rest: format!("{}/{{code}}", url),
+                base: url,
+                ..Default::default()
+            }));
+        let shares: HashMap<String, f64> = [("BINANCE", 2.0), ("HUOBI", 1.0), ("OKEX", 2.0)]
+            .into_iter()
+            .map(|(v, s)| (v.to_string(), s))
+            .collect();
+        let targets: Targets = [("USDT".to_string(), shares)].into_iter().collect();
+        let plan = Rebalancer::new(&client, targets)
+            .min_transfer(1.0)
+            .plan()
+            .await
+            .unwrap();
+
+        let legs: Vec<_> = plan
+            .transfers
+            .iter()
+            .map(|t| (t.to.as_str(), t.amount, t.instant))
+            .collect();
+        assert_eq!(
+            legs,
+            vec![
+                ("OKEX", 400.0, true),
+                ("HUOBI", 50.0, true),
+                ("HUOBI", 50.0, false),
+            ]
+        );
+        assert!(plan.skipped.is_empty());
+    }
+
+    #[test]
+    fn test_plan_display() {
+        let plan = Plan {
+            transfers: vec![],
+            skipped: vec![transfer("BINANCE", "OKEX", 0.5)],
+        };
+        assert_eq!(
+            plan.to_string(),
+            "0.5 USDT BINANCE -> OKEX skipped, below the minimum transfer\n"
+        );
+        assert_eq!(
+            Plan::default().to_string(),
+            "balanced, nothing to transfer\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_execute() {
+        fn page(record: Value) -> Value {
+            json!({"result": {
+                "records": [record], "total": 1, "size": 100, "current": 1,
+                "hitCount": false, "searchCount": true, "pages": 1,
+            }})
+        }
+
+        let url = serve(|path| {
+            let record = |log_id: &str, venue: &str| {
+                json!({
+                    "accountId": "STA-1", "venue": venue, "currency": "USDT", "amount": 10.0,
+                    "fee": 0.0, "targetVenue": "OKEX", "logId": log_id, "status": "COMPLETED",
+                    "logCreatedAt": 1640995200000i64, "logUpdatedAt": 1640995200000i64,
+                    "type": "TRANSFER", "targetAddress": "", "coin": "USDT",
+                    "actionType": "", "actionNote": "", "fromAccountId": "STA-1",
+                    "toAccountId": "STA-1",
+                })
+            };
+            match path {
+                "/bn/asset/transferToVenue" => json!({"result": [record("bn-1", "BINANCE")]}),
+                "/hb/asset/transferToVenue" => {
+                    json!({"error": {"code": 1001, "message": "insufficient balance"}})
+                }
+                "/ok/asset/transferToVenue" => json!({"result": [record("ok-1", "OKEX")]}),
+                // each venue only lists its own transfers
+                "/bn/asset/queryAssetActivityList" => page(record("bn-1", "BINANCE")),
+                "/ok/asset/queryAssetActivityList" => page(record("ok-1", "OKEX")),
+                _ => json!({"error": {"code": 404, "message": path}}),
+            }
+        })
+        .await;
+
+        let client = RestClient::new(ApiFiny::new("key-1", "secret", "STA-1"))
+            .unwrap()
+            .with_venue_registry(VenueRegistry::new(VenueUrls {
+                rest: format!("{}/{{code}}", url),
+                ..Default::default()
+            }));
+        let rebalancer = Rebalancer::new(&client, Targets::new()).tracker(TrackerConfig {
+            initial_interval: Duration::from_millis(10),
+            max_interval: Duration::from_millis(10),
+            timeout: Some(Duration::from_secs(5)),
+        });
+        let plan = Plan {
+            transfers: vec![
+                transfer("BINANCE", "OKEX", 10.0),
+                transfer("HUOBI", "OKEX", 10.0),
+                transfer("OKEX", "BINANCE", 10.0),
+            ],
+            skipped: vec![],
+        };
+
+        let executed = rebalancer.execute(&plan).await;
+        assert_eq!(executed.len(), 3);
+        assert_eq!(executed[0].result.as_ref().unwrap().log_id(), "bn-1");
+        assert!(executed[1].response.is_none());
+        assert!(matches!(
+            executed[1].result,
+            Err(Error::ApiError { code: 1001, .. })
+        ));
+        assert_eq!(executed[2].response.as_ref().unwrap().log_id(), "ok-1");
+        assert_eq!(executed[2].result.as_ref().unwrap().log_id(), "ok-1");
+    }
+
+    #[test]
+    fn test_plan_moves() {
+        let available: BTreeMap<String, f64> = [
+            ("BINANCE".to_string(), 900.0),
+            ("HUOBI".to_string(), 100.0),
+            ("OKEX".to_string(), 0.0),
+        ]
+        .into_iter()
+        .collect();
+        let shares: HashMap<String, f64> = [
+            ("BINANCE".to_string(), 2.0),
+            ("HUOBI".to_string(), 1.0),
+            ("OKEX".to_string(), 2.0),
+        ]
+        .into_iter()
+        .collect();
+
+        let moves = plan_moves(&available, &shares, 1.0);
+        assert_eq!(
+            moves,
+            vec![
+                ("BINANCE".to_string(), "OKEX".to_string(), 400.0),
+                ("BINANCE".to_string(), "HUOBI".to_string(), 100.0),
+            ]
+        );
+
+        assert!(plan_moves(&available, &shares, 1000.0).is_empty());
+    }
+}
@@ -0,0 +1,457 @@
+use super::{
+    rest_client::{ConsolidatedOrderBook, OrderBook, Ticker, TradeOrder},
+    Result,
+};
+use chrono::{serde::ts_milliseconds, DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    cmp::Reverse,
+    collections::BinaryHeap,
+    fs::{self, File},
+    io::{BufRead, BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+};
+
+// market data captured from the rest api
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum MarketData {
+    OrderBook(OrderBook),
+    Trade(Vec<TradeOrder>),
+    Ticker(Ticker),
+    ConsolidatedOrderBook(ConsolidatedOrderBook),
+    // any stream without a dedicated variant, e.g. websocket channels
+    Other { stream: String, payload: Value },
+}
+
+impl MarketData {
+    // stream name, also used as the record kind in files
+    pub fn stream(&self) -> &str {
+        match self {
+            MarketData::OrderBook(_) => "order_book",
+            MarketData::Trade(_) => "trade",
+            MarketData::Ticker(_) => "ticker",
+            MarketData::ConsolidatedOrderBook(_) => "consolidated_order_book",
+            MarketData::Other { stream, .. } => stream,
+        }
+    }
+
+    // exchange side timestamp if the payload carries one
+    pub fn exchange_time(&self) -> Option<DateTime<Utc>> {
+        match self {
+            MarketData::OrderBook(b) => Some(b.updated_at()),
+            MarketData::Trade(trades) => trades.iter().map(|t| t.trade_time()).max(),
+            MarketData::Ticker(t) => Some(t.update_at()),
+            MarketData::ConsolidatedOrderBook(b) => Some(b.update_at()),
+            MarketData::Other { .. } => None,
+        }
+    }
+}
+
+// one recorded market data message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tick {
+    // local time the message was recorded, ticks are replayed in this order
+    #[serde(with = "ts_milliseconds")]
+    pub received_at: DateTime<Utc>,
+    pub venue: Option<String>,
+    pub data: MarketData,
+}
+
+impl Tick {
+    pub fn new(venue: Option<&str>, data: MarketData) -> Tick {
+        Tick {
+            received_at: Utc::now(),
+            venue: venue.map(ToString::to_string),
+            data,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    // line-delimited json, one tick per line
+    Json,
+    // length prefixed (u32 little endian) MessagePack frames
+    Binary,
+}
+
+impl Format {
+    fn extension(&self) -> &'static str {
+        match self {
+            Format::Json => "jsonl",
+            Format::Binary => "mpk",
+        }
+    }
+
+    fn from_path(path: &Path) -> Option<Format> {
+        match path.extension()?.to_str()? {
+            "jsonl" => Some(Format::Json),
+            "mpk" => Some(Format::Binary),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Rotation {
+    Never,
+    // start a new file once the current one reaches this many bytes
+    Size(u64),
+    // start a new file after this much wall time
+    Interval(Duration),
+}
+
+#[derive(Debug, Clone)]
+pub struct RecorderConfig {
+    pub dir: PathBuf,
+    // file name prefix, files are named `{prefix}-{timestamp}.{jsonl|mpk}`
+    pub prefix: String,
+    pub format: Format,
+    pub rotation: Rotation,
+}
+
+impl RecorderConfig {
+    pub fn new(dir: impl Into<PathBuf>, format: Format) -> RecorderConfig {
+        RecorderConfig {
+            dir: dir.into(),
+            prefix: "md".to_string(),
+            format,
+            rotation: Rotation::Interval(Duration::hours(1)),
+        }
+    }
+}
+
+struct RecordFile {
+    writer: BufWriter<File>,
+    opened_at: DateTime<Utc>,
+    written: u64,
+}
+
+// writes ticks to rotating files
+pub struct Recorder {
+    conf: RecorderConfig,
+    current: Option<RecordFile>,
+}
+
+impl Recorder {
+    pub fn new(conf: RecorderConfig) -> Result<Recorder> {
+        fs::create_dir_all(&conf.dir)?;
+        Ok(Recorder {
+            conf,
+            current: None,
+        })
+    }
+
+    // record a message received now
+    pub fn record(&mut self, venue: Option<&str>, data: MarketData) -> Result<()> {
+        self.write(&Tick::new(venue, data))
+    }
+
+    pub fn write(&mut self, tick: &Tick) -> Result<()> {
+        let bytes = match self.conf.format {
+            Format::Json => {
+                let mut line = serde_json::to_vec(tick)?;
+                line.push(b'\n');
+                line
+            }
+            Format::Binary => {
+                let frame = rmp_serde::to_vec(tick)?;
+                let mut bytes = (frame.len() as u32).to_le_bytes().to_vec();
+                bytes.extend(frame);
+                bytes
+            }
+        };
+
+        let file = self.rotate(tick.received_at)?;
+        file.writer.write_all(&bytes)?;
+        file.written += bytes.len() as u64;
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> Result<()> {
+        if let Some(ref mut file) = self.current {
+            file.writer.flush()?;
+        }
+        Ok(())
+    }
+
+    fn rotate(&mut self, now: DateTime<Utc>) -> Result<&mut RecordFile> {
+        let expired = match (&self.current, self.conf.rotation) {
+            (None, _) => true,
+            (Some(_), Rotation::Never) => false,
+            (Some(file), Rotation::Size(max)) => file.written >= max,
+            (Some(file), Rotation::Interval(interval)) => now - file.opened_at >= interval,
+        };
+
+        if expired {
+            self.flush()?;
+            let path = self.next_path(now);
+            self.current = Some(RecordFile {
+                writer: BufWriter::new(File::create(path)?),
+                opened_at: now,
+                written: 0,
+            });
+        }
+        Ok(self.current.as_mut().unwrap())
+    }
+
+    fn next_path(&self, now: DateTime<Utc>) -> PathBuf {
+        let stamp = now.format("%Y%m%dT%H%M%S%3f");
+        let ext = self.conf.format.extension();
+        let mut path = self
+            .conf
+            .dir
+            .join(format!("{}-{}.{}", self.conf.prefix, stamp, ext));
+        // several rotations within the same millisecond
+        let mut seq = 1;
+        while path.exists() {
+            path = self
+                .conf
+                .dir
+                .join(format!("{}-{}-{}.{}", self.conf.prefix, stamp, seq, ext));
+            seq += 1;
+        }
+        path
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+// reads ticks back from a single file
+struct TickFile {
+    format: Format,
+    reader: BufReader<File>,
+}
+
+impl TickFile {
+    fn open(path: &Path) -> Result<Option<TickFile>> {
+        Ok(match Format::from_path(path) {
+            Some(format) => Some(TickFile {
+                format,
+                reader: BufReader::new(File::open(path)?),
+            }),
+            None => None,
+        })
+    }
+
+    fn next_tick(&mut self) -> Result<Option<Tick>> {
+        match self.format {
+            Format::Json => {
+                let mut line = String::new();
+                loop {
+                    line.clear();
+                    if self.reader.read_line(&mut line)? == 0 {
+                        return Ok(None);
+                    }
+                    if !line.trim().is_empty() {
+                        return Ok(Some(serde_json::from_str(&line)?));
+                    }
+                }
+            }
+            Format::Binary => {
+                let mut len = [0u8; 4];
+                match self.reader.read_exact(&mut len) {
+                    Ok(()) => {}
+                    Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+                    Err(e) => return Err(e.into()),
+                }
+                let mut frame = vec![0u8; u32::from_le_bytes(len) as usize];
+                self.reader.read_exact(&mut frame)?;
+                Ok(Some(rmp_serde::from_slice(&frame)?))
+            }
+        }
+    }
+}
+
+// merges recorded files into one stream ordered by `received_at`
+pub struct TickReader {
+    files: Vec<TickFile>,
+    heads: BinaryHeap<Reverse<(DateTime<Utc>, usize)>>,
+    pending: Vec<Option<Tick>>,
+}
+
+impl TickReader {
+    pub fn open<P: AsRef<Path>>(paths: &[P]) -> Result<TickReader> {
+        let mut files = vec![];
+        for path in paths {
+            if let Some(file) = TickFile::open(path.as_ref())? {
+                files.push(file);
+            }
+        }
+
+        let mut reader = TickReader {
+            pending: files.iter().map(|_| None).collect(),
+            files,
+            heads: BinaryHeap::new(),
+        };
+        for idx in 0..reader.files.len() {
+            reader.advance(idx)?;
+        }
+        Ok(reader)
+    }
+
+    // open every recorded file in `dir` whose name starts with `prefix`
+    pub fn open_dir(dir: impl AsRef<Path>, prefix: &str) -> Result<TickReader> {
+        let mut paths = vec![];
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            let matched = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .map(|n| n.starts_with(&format!("{}-", prefix)))
+                .unwrap_or(false);
+            if matched && Format::from_path(&path).is_some() {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+        TickReader::open(&paths)
+    }
+
+    fn advance(&mut self, idx: usize) -> Result<()> {
+        if let Some(tick) = self.files[idx].next_tick()? {
+            self.heads.push(Reverse((tick.received_at, idx)));
+            self.pending[idx] = Some(tick);
+        }
+        Ok(())
+    }
+}
+
+impl Iterator for TickReader {
+    type Item = Result<Tick>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Reverse((_, idx)) = self.heads.pop()?;
+        let tick = self.pending[idx].take();
+        if let Err(e) = self.advance(idx) {
+            return Some(Err(e));
+        }
+        tick.map(Ok)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Speed {
+    // keep the recorded gaps between ticks
+    Original,
+    // divide the recorded gaps by this factor
+    Accelerated(f64),
+    // no waiting at all, for deterministic tests
+    Unthrottled,
+}
+
+// replays recorded ticks with their original or accelerated timing
+pub struct Replayer {
+    reader: TickReader,
+    speed: Speed,
+    last: Option<DateTime<Utc>>,
+}
+
+impl Replayer {
+    pub fn new(reader: TickReader, speed: Speed) -> Replayer {
+        Replayer {
+            reader,
+            speed,
+            last: None,
+        }
+    }
+
+    pub async fn next(&mut self) -> Option<Result<Tick>> {
+        let tick = match self.reader.next()? {
+            Ok(tick) => tick,
+            Err(e) => return Some(Err(e)),
+        };
+
+        if let Some(last) = self.last {
+            let gap = (tick.received_at - last).to_std().unwrap_or_default();
+            let wait = match self.speed {
+                Speed::Original => Some(gap),
+                Speed::Accelerated(factor) if factor > 0.0 => Some(gap.div_f64(factor)),
+                _ => None,
+            };
+            if let Some(wait) = wait {
+                tokio::time::sleep(wait).await;
+            }
+        }
+        self.last = Some(tick.received_at);
+        Some(Ok(tick))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    fn tick(ms: i64, n: i64) -> Tick {
+        Tick {
+            received_at: Utc.timestamp_millis_opt(ms).unwrap(),
+            venue: Some("BINANCE".to_string()),
+            data: MarketData::Other {
+                stream: "test".to_string(),
+                payload: json!({ "n": n }),
+            },
+        }
+    }
+
+    fn record_and_read(format: Format) {
+        let dir = std::env::temp_dir().join(format!(
+            "apifiny-recorder-{:?}-{}",
+            format,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+
+        let mut conf = RecorderConfig::new(&dir, format);
+        conf.rotation = Rotation::Size(1);
+        let mut recorder = Recorder::new(conf.clone()).unwrap();
+        for (ms, n) in [(1000, 0), (3000, 2)] {
+            recorder.write(&tick(ms, n)).unwrap();
+        }
+        drop(recorder);
+
+        conf.prefix = "other".to_string();
+        let mut recorder = Recorder::new(conf).unwrap();
+        recorder.write(&tick(2000, 1)).unwrap();
+        drop(recorder);
+
+        let reader = TickReader::open_dir(&dir, "md").unwrap();
+        assert_eq!(reader.files.len(), 2);
+
+        let mut paths: Vec<_> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|e| e.unwrap().path())
+            .collect();
+        paths.sort();
+        let ticks: Vec<Tick> = TickReader::open(&paths)
+            .unwrap()
+            .collect::<Result<_>>()
+            .unwrap();
+        let order: Vec<_> = ticks
+            .iter()
+            .map(|t| match &t.data {
+                MarketData::Other { payload, .. } => payload["n"].as_i64().unwrap(),
+                _ => unreachable!(),
+            })
+            .collect();
+        assert_eq!(order, vec![0, 1, 2]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_record_and_read_json() {
+        record_and_read(Format::Json);
+    }
+
+    #[test]
+    fn test_record_and_read_binary() {
+        record_and_read(Format::Binary);
+    }
+}
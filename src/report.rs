@@ -0,0 +1,385 @@
+use super::{
+    fee::{FeeModel, Liquidity},
+    position::{CostMethod, Fill, PositionBook},
+    rest_client::{AccountHistory, OrderResponse, VenueClient},
+    Result, Symbol,
+};
+use chrono::{DateTime, TimeZone, Utc};
+use futures::TryStreamExt;
+use serde::Serialize;
+use std::{collections::BTreeMap, io::Write};
+
+// one balance change of one currency
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LedgerEntry {
+    pub time: DateTime<Utc>,
+    pub currency: String,
+    // BUY and SELL for fills, the activity type otherwise
+    pub kind: String,
+    // negative when leaving the account
+    pub amount: f64,
+    pub fee: f64,
+    // order id or activity log id
+    pub reference: String,
+}
+
+// realized gains of one symbol within the report range, in its quote currency
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RealizedGain {
+    pub symbol: String,
+    // net of fees
+    pub realized_pnl: f64,
+    pub fees: f64,
+    // still open at the end of the range
+    pub open_quantity: f64,
+}
+
+// totals of one currency within the report range
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CurrencySummary {
+    pub currency: String,
+    pub deposits: f64,
+    pub withdrawals: f64,
+    pub transfers_in: f64,
+    pub transfers_out: f64,
+    // net change from fills
+    pub trades: f64,
+    pub fees: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Report {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub method: CostMethod,
+    // by currency, then time
+    pub ledger: Vec<LedgerEntry>,
+    pub gains: Vec<RealizedGain>,
+    pub summary: Vec<CurrencySummary>,
+}
+
+// Sign of an account activity. The type gives the direction where it has
+// one, other transfers go by which side names the activity's own account,
+// none when neither does.
+fn signed_amount(record: &AccountHistory) -> Option<f64> {
+    match record.kind() {
+        "DEPOSIT" | "INCOMING" => Some(record.amount()),
+        "WITHDRAW" | "OUTGOING" => Some(-record.amount()),
+        _ if record.to_account_id() == record.account_id() => Some(record.amount()),
+        _ if record.from_account_id() == record.account_id() => Some(-record.amount()),
+        _ => None,
+    }
+}
+
+impl Report {
+    // Report on [start, end). Gains replay every fill before `end` with
+    // `method`, so lots opened before the range are closed at their cost.
+    pub fn build(
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        method: CostMethod,
+        history: &[AccountHistory],
+        fills: &[Fill],
+    ) -> Report {
+        let in_range = |t: DateTime<Utc>| t >= start && t < end;
+        let mut ledger = vec![];
+
+        for record in history {
+            if !in_range(record.log_created_at()) || record.status() != "COMPLETED" {
+                continue;
+            }
+            // a wrong sign would skew the totals, leave it to the reader
+            let Some(amount) = signed_amount(record) else {
+                tracing::warn!(
+                    "{} {} has no direction, left out of the report",
+                    record.kind(),
+                    record.log_id()
+                );
+                continue;
+            };
+            ledger.push(LedgerEntry {
+                time: record.log_created_at(),
+                currency: record.currency().to_uppercase(),
+                kind: record.kind().to_string(),
+                amount,
+                fee: record.fee(),
+                reference: record.log_id().to_string(),
+            });
+        }
+
+        let mut fills: Vec<_> = fills.iter().filter(|f| f.time < end).collect();
+        fills.sort_by_key(|f| f.time);
+        let mut book = PositionBook::new(method);
+        let mut before = None;
+        for fill in fills {
+            if fill.time >= start && before.is_none() {
+                before = Some(book.clone());
+            }
+            book.apply(fill);
+            if fill.time >= start {
+                ledger.extend(fill_entries(fill));
+            }
+        }
+        let before = before.unwrap_or_else(|| book.clone());
+
+        let gains = book
+            .positions()
+            .map(|p| {
+                let (pnl, fees) = before
+                    .position(&p.symbol)
+                    .map(|b| (b.realized_pnl, b.fees))
+                    .unwrap_or((0.0, 0.0));
+                RealizedGain {
+                    symbol: p.symbol.clone(),
+                    realized_pnl: p.realized_pnl - pnl,
+                    fees: p.fees - fees,
+                    open_quantity: p.quantity,
+                }
+            })
+            .filter(|g| g.realized_pnl != 0.0 || g.fees != 0.0)
+            .collect();
+
+        ledger.sort_by(|a, b| a.currency.cmp(&b.currency).then(a.time.cmp(&b.time)));
+        let summary = summarize(&ledger);
+        Report {
+            start,
+            end,
+            method,
+            ledger,
+            gains,
+            summary,
+        }
+    }
+
+    // entries of one currency, oldest first
+    pub fn ledger_of<'a>(&'a self, currency: &'a str) -> impl Iterator<Item = &'a LedgerEntry> {
+        self.ledger
+            .iter()
+            .filter(move |e| e.currency.eq_ignore_ascii_case(currency))
+    }
+
+    pub fn write_json<W: Write>(&self, writer: W) -> Result<()> {
+        Ok(serde_json::to_writer_pretty(writer, self)?)
+    }
+
+    // ledger.csv, gains.csv and summary.csv in `dir`
+    #[cfg(feature = "export")]
+    pub fn write_csv(&self, dir: impl AsRef<std::path::Path>) -> Result<()> {
+        use super::export::write_csv;
+        use std::fs::File;
+
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+        write_csv(File::create(dir.join("ledger.csv"))?, &self.ledger)?;
+        write_csv(File::create(dir.join("gains.csv"))?, &self.gains)?;
+        write_csv(File::create(dir.join("summary.csv"))?, &self.summary)
+    }
+}
+
+// base and quote entries of a fill, nothing if the symbol can't be split
+fn fill_entries(fill: &Fill) -> Vec<LedgerEntry> {
+    let symbol: Symbol = match fill.symbol.parse() {
+        Ok(symbol) => symbol,
+        Err(_) => {
+            tracing::warn!("no ledger entries for unknown symbol {}", fill.symbol);
+            return vec![];
+        }
+    };
+    let sign = if fill.side.eq_ignore_ascii_case("SELL") {
+        -1.0
+    } else {
+        1.0
+    };
+    let entry = |currency: String, amount: f64, fee: f64| LedgerEntry {
+        time: fill.time,
+        currency,
+        kind: fill.side.to_uppercase(),
+        amount,
+        fee,
+        reference: fill.order_id.clone(),
+    };
+    vec![
+        entry(symbol.base, sign * fill.quantity, 0.0),
+        entry(symbol.quote, -sign * fill.quantity * fill.price, fill.fee),
+    ]
+}
+
+fn summarize(ledger: &[LedgerEntry]) -> Vec<CurrencySummary> {
+    let mut summary: BTreeMap<&str, CurrencySummary> = BTreeMap::new();
+    for entry in ledger {
+        let s = summary
+            .entry(&entry.currency)
+            .or_insert_with(|| CurrencySummary {
+                currency: entry.currency.clone(),
+                ..Default::default()
+            });
+        s.fees += entry.fee;
+        match entry.kind.as_str() {
+            "BUY" | "SELL" => s.trades += entry.amount,
+            "DEPOSIT" => s.deposits += entry.amount,
+            "WITHDRAW" => s.withdrawals -= entry.amount,
+            _ if entry.amount >= 0.0 => s.transfers_in += entry.amount,
+            _ => s.transfers_out -= entry.amount,
+        }
+    }
+    summary.into_values().collect()
+}
+
+#[derive(Debug, Clone)]
+pub struct ReportQuery {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub method: CostMethod,
+    // fills are fetched from here on, so that lots opened before `start` are known
+    pub fills_since: DateTime<Utc>,
+    // estimates fill fees, the order list carries none
+    pub fees: Option<FeeModel>,
+}
+
+impl ReportQuery {
+    pub fn new(start: DateTime<Utc>, end: DateTime<Utc>) -> ReportQuery {
+        ReportQuery {
+            start,
+            end,
+            method: CostMethod::Fifo,
+            fills_since: Utc.timestamp_millis_opt(0).unwrap(),
+            fees: None,
+        }
+    }
+}
+
+impl<'a> VenueClient<'a> {
+    // account activities and fills of the handle's venue, as a report
+    pub async fn report(&self, query: ReportQuery) -> Result<Report> {
+        let history: Vec<AccountHistory> = self
+            .account_history_stream(query.start, query.end)
+            .try_collect()
+            .await?;
+        let orders: Vec<OrderResponse> = self
+            .filled_order_history(Default::default(), query.fills_since, query.end)
+            .try_collect()
+            .await?;
+
+        let mut fills = Vec::with_capacity(orders.len());
+        for order in &orders {
+            let mut fill = Fill::from(order);
+            if let Some(fees) = &query.fees {
+                let schedule = fees.schedule(*self, order.symbol()).await?;
+                fill.fee = schedule.fee(fill.quantity, fill.price, Liquidity::of_order(order));
+            }
+            fills.push(fill);
+        }
+
+        Ok(Report::build(
+            query.start,
+            query.end,
+            query.method,
+            &history,
+            &fills,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn at(day: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2022, 1, day, 0, 0, 0).unwrap()
+    }
+
+    fn fill(day: u32, side: &str, quantity: f64, price: f64) -> Fill {
+        Fill {
+            order_id: format!("{}", day),
+            symbol: "BTCUSDT".to_string(),
+            side: side.to_string(),
+            quantity,
+            price,
+            fee: 1.0,
+            time: at(day),
+        }
+    }
+
+    #[test]
+    fn test_report() {
+        let deposit: AccountHistory = serde_json::from_value(json!({
+            "accountId": "STA-1",
+            "currency": "USDT",
+            "status": "COMPLETED",
+            "type": "DEPOSIT",
+            "amount": 1000.0,
+            "fee": 0.0,
+            "targetAddress": "",
+            "coin": "USDT",
+            "logId": "L1",
+            "logCreatedAt": at(10).timestamp_millis(),
+            "logUpdatedAt": at(10).timestamp_millis(),
+            "actionType": "",
+            "actionNote": "",
+            "fromAccountId": "",
+            "toAccountId": "STA-1",
+        }))
+        .unwrap();
+        let fills = [
+            fill(1, "BUY", 1.0, 100.0),
+            fill(2, "BUY", 1.0, 200.0),
+            fill(11, "SELL", 1.0, 300.0),
+        ];
+
+        let fifo = Report::build(
+            at(5),
+            at(20),
+            CostMethod::Fifo,
+            std::slice::from_ref(&deposit),
+            &fills,
+        );
+        assert_eq!(fifo.gains[0].realized_pnl, 199.0);
+        assert_eq!(fifo.gains[0].open_quantity, 1.0);
+        let lifo = Report::build(at(5), at(20), CostMethod::Lifo, &[deposit], &fills);
+        assert_eq!(lifo.gains[0].realized_pnl, 99.0);
+
+        let usdt: Vec<_> = fifo.ledger_of("USDT").collect();
+        assert_eq!(usdt.len(), 2);
+        assert_eq!(fifo.ledger_of("BTC").count(), 1);
+        let usdt = fifo.summary.iter().find(|s| s.currency == "USDT").unwrap();
+        assert_eq!(usdt.deposits, 1000.0);
+        assert_eq!(usdt.trades, 300.0);
+        assert_eq!(usdt.fees, 1.0);
+    }
+
+    #[test]
+    fn test_signed_amount() {
+        let record = |kind: &str, from: Option<&str>, to: Option<&str>| {
+            let mut record = json!({
+                "accountId": "STA-1",
+                "currency": "USDT",
+                "status": "COMPLETED",
+                "type": kind,
+                "amount": 10.0,
+                "fee": 0.0,
+                "targetAddress": "",
+                "coin": "USDT",
+                "logId": "L1",
+                "logCreatedAt": 0,
+                "logUpdatedAt": 0,
+                "actionType": "",
+                "actionNote": "",
+            });
+            if let Some(from) = from {
+                record["fromAccountId"] = json!(from);
+            }
+            if let Some(to) = to {
+                record["toAccountId"] = json!(to);
+            }
+            serde_json::from_value::<AccountHistory>(record).unwrap()
+        };
+
+        assert_eq!(signed_amount(&record("DEPOSIT", None, None)), Some(10.0));
+        assert_eq!(signed_amount(&record("OUTGOING", None, None)), Some(-10.0));
+        let transfer = |from, to| signed_amount(&record("TRANSFER", from, to));
+        assert_eq!(transfer(Some("STA-2"), Some("STA-1")), Some(10.0));
+        assert_eq!(transfer(Some("STA-1"), None), Some(-10.0));
+        assert_eq!(transfer(None, None), None);
+    }
+}
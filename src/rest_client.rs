@@ -1,7 +1,9 @@
 use super::{
+    clock::Clock,
     http_error,
-    utils::{get_http_client, signature_req},
-    Result,
+    utils::{get_http_client, set_query},
+    venues::VenueRegistry,
+    RateLimiter, Result, Signer, Venue,
 };
 use chrono::{
     serde::{ts_milliseconds, ts_milliseconds_option},
@@ -10,19 +12,94 @@ use chrono::{
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 
-// OTHER
-const BASE_URL_REST_OTHER: &str = "https://api.apifiny.com";
-// const BASE_URL_FIX_OTHER: &str = "fix.api.apifiny.com:1443";
-
 // https://doc.apifiny.com/connect/#rest-api
+#[derive(Clone)]
 pub struct RestClient {
     conf: crate::ApiFiny,
-    venue: Option<super::Venue>,
+    // throttles the public market data endpoints
+    limiter: RateLimiter,
+    // signs every request
+    signer: Signer,
+    // connection pool, shared by clones
+    http: reqwest::Client,
+    // venues and hosts, shared by clones
+    venues: VenueRegistry,
 }
 
 impl RestClient {
-    pub fn new(conf: super::ApiFiny, venue: Option<super::Venue>) -> RestClient {
-        RestClient { conf, venue }
+    pub fn new(conf: super::ApiFiny) -> Result<RestClient> {
+        Ok(RestClient {
+            signer: Signer::from_config(&conf)?,
+            conf,
+            limiter: RateLimiter::default(),
+            http: get_http_client()?,
+            venues: VenueRegistry::default(),
+        })
+    }
+
+    // share one connection pool between several clients
+    pub fn with_http_client(mut self, http: reqwest::Client) -> RestClient {
+        self.http = http;
+        self
+    }
+
+    pub fn account_id(&self) -> &str {
+        &self.conf.apifiny_account_id
+    }
+
+    // sign with a customized signer, e.g. another token lifetime
+    pub fn with_signer(mut self, signer: Signer) -> RestClient {
+        self.signer = signer;
+        self
+    }
+
+    pub fn signer(&self) -> &Signer {
+        &self.signer
+    }
+
+    // sign with `clock`, e.g. the one kept by a `ClockSync`
+    pub fn with_clock(mut self, clock: Clock) -> RestClient {
+        self.signer = self.signer.with_clock(clock);
+        self
+    }
+
+    // server corrected time, for signing and timestamped params
+    pub fn clock(&self) -> &Clock {
+        self.signer.clock()
+    }
+
+    // share one rate limiter between several clients
+    pub fn with_rate_limiter(mut self, limiter: RateLimiter) -> RestClient {
+        self.limiter = limiter;
+        self
+    }
+
+    pub fn rate_limiter(&self) -> &RateLimiter {
+        &self.limiter
+    }
+
+    // share one venue registry between several clients, or use other hosts
+    pub fn with_venue_registry(mut self, venues: VenueRegistry) -> RestClient {
+        self.venues = venues;
+        self
+    }
+
+    pub fn venues(&self) -> &VenueRegistry {
+        &self.venues
+    }
+
+    // private endpoints of the account's `venue` sub account, at the hosts
+    // of the client's registry
+    pub fn venue(&self, venue: Venue) -> VenueClient<'_> {
+        VenueClient {
+            client: self,
+            venue: self.venues.resolve(venue),
+        }
+    }
+
+    // like `venue`, by name ignoring case, the venue must be enabled
+    pub fn venue_named(&self, name: &str) -> Result<VenueClient<'_>> {
+        Ok(self.venue(self.venues.get(name)?))
     }
 
     // Base Information
@@ -31,7 +108,8 @@ impl RestClient {
     pub async fn list_venue_info(&self, venue: &str) -> Result<ApiFinyResponse<Vec<VenueInfo>>> {
         let req_url = format!(
             "{}/ac/v2/{}/utils/listVenueInfo",
-            BASE_URL_REST_OTHER, venue
+            self.venues.urls().base,
+            venue
         );
         Ok(self
             .do_http(reqwest::Method::GET, req_url, None, None)
@@ -42,7 +120,11 @@ impl RestClient {
 
     // https://doc.apifiny.com/connect/#query-list-currencies
     pub async fn list_currency(&self, venue: &str) -> Result<ApiFinyResponse<Vec<CurrencyInfo>>> {
-        let req_url = format!("{}/ac/v2/{}/utils/listCurrency", BASE_URL_REST_OTHER, venue);
+        let req_url = format!(
+            "{}/ac/v2/{}/utils/listCurrency",
+            self.venues.urls().base,
+            venue
+        );
         Ok(self
             .do_http(reqwest::Method::GET, req_url, None, None)
             .await?
@@ -54,7 +136,8 @@ impl RestClient {
     pub async fn list_symbol_info(&self, venue: &str) -> Result<ApiFinyResponse<Vec<SymbolInfo>>> {
         let req_url = format!(
             "{}/ac/v2/{}/utils/listSymbolInfo",
-            BASE_URL_REST_OTHER, venue
+            self.venues.urls().base,
+            venue
         );
         Ok(self
             .do_http(reqwest::Method::GET, req_url, None, None)
@@ -67,7 +150,8 @@ impl RestClient {
     pub async fn current_time_millis(&self, venue: &str) -> Result<ApiFinyResponse<i64>> {
         let req_url = format!(
             "{}/ac/v2/{}/utils/currentTimeMillis",
-            BASE_URL_REST_OTHER, venue
+            self.venues.urls().base,
+            venue
         );
         Ok(self
             .do_http(reqwest::Method::GET, req_url, None, None)
@@ -83,8 +167,11 @@ impl RestClient {
     pub async fn order_book(&self, symbol: &str, venue: &str) -> Result<OrderBook> {
         let req_url = format!(
             "{}/md/orderbook/v1/{}/{}",
-            BASE_URL_REST_OTHER, symbol, venue
+            self.venues.urls().base,
+            symbol,
+            venue
         );
+        self.limiter.acquire().await;
         Ok(self
             .do_http(reqwest::Method::GET, req_url, None, None)
             .await?
@@ -94,7 +181,13 @@ impl RestClient {
 
     // https://doc.apifiny.com/connect/#trades-rest-api
     pub async fn trade(&self, symbol: &str, venue: &str) -> Result<Vec<TradeOrder>> {
-        let req_url = format!("{}/md/trade/v1/{}/{}", BASE_URL_REST_OTHER, symbol, venue);
+        let req_url = format!(
+            "{}/md/trade/v1/{}/{}",
+            self.venues.urls().base,
+            symbol,
+            venue
+        );
+        self.limiter.acquire().await;
         Ok(self
             .do_http(reqwest::Method::GET, req_url, None, None)
             .await?
@@ -120,15 +213,14 @@ impl RestClient {
     ) -> Result<Vec<KLine>> {
         let req_url = format!(
             "{}/md/kline/v1/{}/{}/{}/{}",
-            BASE_URL_REST_OTHER, venue, base, quote, period
+            self.venues.urls().base,
+            venue,
+            base,
+            quote,
+            period
         );
-        let mut query = None;
-        if start_time.is_some() && end_time.is_some() {
-            query = Some(json!({
-                "startTime": start_time.unwrap().timestamp_subsec_millis(),
-                "endTime": end_time.unwrap().timestamp_subsec_millis(),
-            }))
-        }
+        let query = kline_query(start_time, end_time);
+        self.limiter.acquire().await;
         Ok(self
             .do_http(reqwest::Method::GET, req_url, query, None)
             .await?
@@ -138,7 +230,13 @@ impl RestClient {
 
     // https://doc.apifiny.com/connect/#ticker-rest-api
     pub async fn ticker(&self, symbol: &str, venue: &str) -> Result<Ticker> {
-        let req_url = format!("{}/md/ticker/v1/{}/{}", BASE_URL_REST_OTHER, symbol, venue);
+        let req_url = format!(
+            "{}/md/ticker/v1/{}/{}",
+            self.venues.urls().base,
+            symbol,
+            venue
+        );
+        self.limiter.acquire().await;
         Ok(self
             .do_http(reqwest::Method::GET, req_url, None, None)
             .await?
@@ -148,11 +246,12 @@ impl RestClient {
 
     // https://doc.apifiny.com/connect/#consolidated-order-book-rest-api
     pub async fn consolidated_order_book(&self, symbol: &str) -> Result<ConsolidatedOrderBook> {
-        let req_url = format!("{}/md/cob/v1/{}", BASE_URL_REST_OTHER, symbol);
+        let req_url = format!("{}/md/cob/v1/{}", self.venues.urls().base, symbol);
         // let s = self.do_http(req_url, None, None).await?.text().await?;
         // println!("==>{}", s);
         // Ok(())
 
+        self.limiter.acquire().await;
         Ok(self
             .do_http(reqwest::Method::GET, req_url, None, None)
             .await?
@@ -160,120 +259,175 @@ impl RestClient {
             .await?)
     }
 
+    // do real http request
+    async fn do_http(
+        &self,
+        method: reqwest::Method,
+        req_url: String,
+        query: Option<Value>,
+        body: Option<Value>,
+    ) -> Result<reqwest::Response> {
+        let mut req = self
+            .http
+            .request(method, &req_url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .build()?;
+
+        // set query params
+        if let Some(query) = query {
+            set_query(req.url_mut(), &query)?;
+        }
+
+        // set body params
+        if let Some(body) = body {
+            *req.body_mut() = Some(serde_json::to_vec(&body)?.into());
+        }
+
+        self.signer.sign_request(&mut req)?;
+
+        let resp = self.http.execute(req).await?;
+
+        let status_code = resp.status();
+        if !status_code.is_success() {
+            let msg = resp.text().await?;
+            return Err(http_error(req_url, status_code, msg));
+        }
+
+        Ok(resp)
+    }
+}
+
+// Private endpoints of one venue sub account, from `RestClient::venue`.
+#[derive(Clone, Copy)]
+pub struct VenueClient<'a> {
+    client: &'a RestClient,
+    venue: Venue,
+}
+
+impl<'a> VenueClient<'a> {
+    pub fn client(&self) -> &'a RestClient {
+        self.client
+    }
+
+    pub fn venue(&self) -> Venue {
+        self.venue
+    }
+
+    // the same account on another venue
+    pub fn with_venue(&self, venue: Venue) -> VenueClient<'a> {
+        self.client.venue(venue)
+    }
+
     // Account
 
     // https://doc.apifiny.com/connect/#query-account-info
     pub async fn query_account_info(&self) -> Result<ApiFinyResponse<Account>> {
-        if let Some(ref venue) = self.venue {
-            let req_url = format!("{}/account/queryAccountInfo", venue.rest);
-
-            let query = Some(json!({
-                "accountId": self.conf.apifiny_account_id,
-                "venue": venue.name,
-            }));
-
-            return Ok(self
-                .do_http(reqwest::Method::GET, req_url, query, None)
-                .await?
-                .json()
-                .await?);
-        }
-        Err(super::Error::VenueNotSet())
+        let venue = &self.venue;
+        let req_url = format!("{}/account/queryAccountInfo", venue.rest);
+
+        let query = Some(json!({
+            "accountId": self.client.conf.apifiny_account_id,
+            "venue": venue.name,
+        }));
+
+        Ok(self
+            .client
+            .do_http(reqwest::Method::GET, req_url, query, None)
+            .await?
+            .json()
+            .await?)
     }
 
     // Query Account Asset
     // https://doc.apifiny.com/connect/#query-account-asset
     pub async fn list_balance(&self) -> Result<ApiFinyResponse<Vec<Balance>>> {
-        if let Some(ref venue) = self.venue {
-            let req_url = format!("{}/asset/listBalance", venue.rest);
-
-            let query = Some(json!({
-                "accountId": self.conf.apifiny_account_id,
-                "venue": venue.name,
-            }));
-
-            return Ok(self
-                .do_http(reqwest::Method::GET, req_url, query, None)
-                .await?
-                .json()
-                .await?);
-        }
-        Err(super::Error::VenueNotSet())
+        let venue = &self.venue;
+        let req_url = format!("{}/asset/listBalance", venue.rest);
+
+        let query = Some(json!({
+            "accountId": self.client.conf.apifiny_account_id,
+            "venue": venue.name,
+        }));
+
+        Ok(self
+            .client
+            .do_http(reqwest::Method::GET, req_url, query, None)
+            .await?
+            .json()
+            .await?)
     }
 
     // Get Deposit Address
     // https://doc.apifiny.com/connect/#get-deposit-address
     pub async fn query_address(&self, coin: &str) -> Result<ApiFinyResponse<Vec<DepositAddress>>> {
-        if let Some(ref venue) = self.venue {
-            let req_url = format!("{}/asset/queryAddress", venue.rest);
-
-            let query = Some(json!({
-                "accountId": self.conf.apifiny_account_id,
-                "venue": venue.name,
-                "coin": coin,
-            }));
-
-            return Ok(self
-                .do_http(reqwest::Method::GET, req_url, query, None)
-                .await?
-                .json()
-                .await?);
-        }
-        Err(super::Error::VenueNotSet())
+        let venue = &self.venue;
+        let req_url = format!("{}/asset/queryAddress", venue.rest);
+
+        let query = Some(json!({
+            "accountId": self.client.conf.apifiny_account_id,
+            "venue": venue.name,
+            "coin": coin,
+        }));
+
+        Ok(self
+            .client
+            .do_http(reqwest::Method::GET, req_url, query, None)
+            .await?
+            .json()
+            .await?)
     }
 
     // Create a Withdraw Ticket
     // https://doc.apifiny.com/connect/#create-a-withdraw-ticket
     pub async fn create_withdraw_ticket(&self) -> Result<ApiFinyResponse<WithdrawTicket>> {
-        if let Some(ref venue) = self.venue {
-            let req_url = format!("{}/asset/createWithdrawTicket", venue.rest);
-
-            let query = Some(json!({
-                "accountId": self.conf.apifiny_account_id,
-                "venue": venue.name,
-            }));
-
-            return Ok(self
-                .do_http(reqwest::Method::GET, req_url, query, None)
-                .await?
-                .json()
-                .await?);
-        }
-        Err(super::Error::VenueNotSet())
+        let venue = &self.venue;
+        let req_url = format!("{}/asset/createWithdrawTicket", venue.rest);
+
+        let query = Some(json!({
+            "accountId": self.client.conf.apifiny_account_id,
+            "venue": venue.name,
+        }));
+
+        Ok(self
+            .client
+            .do_http(reqwest::Method::GET, req_url, query, None)
+            .await?
+            .json()
+            .await?)
     }
 
     // Create a Withdraw Request
     // https://doc.apifiny.com/connect/#create-a-withdraw-request
+    // sends as is, `Treasury::withdraw` adds the allowlist and approval
     pub async fn create_withdraw(
         &self,
         params: CreateWithdrawParams,
     ) -> Result<ApiFinyResponse<Vec<Withdraw>>> {
-        if let Some(ref venue) = self.venue {
-            let req_url = format!("{}/asset/withdraw", venue.rest);
-
-            let mut body = json!({
-                "accountId": self.conf.apifiny_account_id,
-                "venue": venue.name,
-            });
-            let params = serde_json::to_value(params)?;
-
-            super::utils::merge(&mut body, &params);
-            // println!("==>{}", body);
-            // let s = self
-            //     .do_http(reqwest::Method::GET, req_url, None, Some(body))
-            //     .await?
-            //     .text()
-            //     .await?;
-            // println!("==>{}", s);
-            // return Ok(());
-
-            return Ok(self
-                .do_http(reqwest::Method::POST, req_url, None, Some(body))
-                .await?
-                .json()
-                .await?);
-        }
-        Err(super::Error::VenueNotSet())
+        let venue = &self.venue;
+        let req_url = format!("{}/asset/withdraw", venue.rest);
+
+        let mut body = json!({
+            "accountId": self.client.conf.apifiny_account_id,
+            "venue": venue.name,
+        });
+        let params = serde_json::to_value(params)?;
+
+        super::utils::merge(&mut body, &params);
+        // println!("==>{}", body);
+        // let s = self
+        //     .do_http(reqwest::Method::GET, req_url, None, Some(body))
+        //     .await?
+        //     .text()
+        //     .await?;
+        // println!("==>{}", s);
+        // return Ok(());
+
+        Ok(self
+            .client
+            .do_http(reqwest::Method::POST, req_url, None, Some(body))
+            .await?
+            .json()
+            .await?)
     }
 
     // Transfer Between Venues
@@ -282,24 +436,23 @@ impl RestClient {
         &self,
         params: TransferBetweenVenuesParams,
     ) -> Result<ApiFinyResponse<Vec<TransferBetweenVenuesResponse>>> {
-        if let Some(ref venue) = self.venue {
-            let req_url = format!("{}/asset/transferToVenue", venue.rest);
-
-            let mut query = json!({
-                "accountId": self.conf.apifiny_account_id,
-                "venue": venue.name,
-            });
-            let params = serde_json::to_value(params)?;
-
-            super::utils::merge(&mut query, &params);
-
-            return Ok(self
-                .do_http(reqwest::Method::GET, req_url, Some(query), None)
-                .await?
-                .json()
-                .await?);
-        }
-        Err(super::Error::VenueNotSet())
+        let venue = &self.venue;
+        let req_url = format!("{}/asset/transferToVenue", venue.rest);
+
+        let mut query = json!({
+            "accountId": self.client.conf.apifiny_account_id,
+            "venue": venue.name,
+        });
+        let params = serde_json::to_value(params)?;
+
+        super::utils::merge(&mut query, &params);
+
+        Ok(self
+            .client
+            .do_http(reqwest::Method::GET, req_url, Some(query), None)
+            .await?
+            .json()
+            .await?)
     }
 
     // Query Account History
@@ -308,23 +461,22 @@ impl RestClient {
         &self,
         params: QueryAccountHistoryParams,
     ) -> Result<ApiFinyResponse<PagationResponse<AccountHistory>>> {
-        if let Some(ref venue) = self.venue {
-            let req_url = format!("{}/asset/queryAssetActivityList", venue.rest);
+        let venue = &self.venue;
+        let req_url = format!("{}/asset/queryAssetActivityList", venue.rest);
 
-            let mut query = json!({
-                "accountId": self.conf.apifiny_account_id,
-            });
-            let params = serde_json::to_value(params)?;
+        let mut query = json!({
+            "accountId": self.client.conf.apifiny_account_id,
+        });
+        let params = serde_json::to_value(params)?;
 
-            super::utils::merge(&mut query, &params);
+        super::utils::merge(&mut query, &params);
 
-            return Ok(self
-                .do_http(reqwest::Method::GET, req_url, Some(query), None)
-                .await?
-                .json()
-                .await?);
-        }
-        Err(super::Error::VenueNotSet())
+        Ok(self
+            .client
+            .do_http(reqwest::Method::GET, req_url, Some(query), None)
+            .await?
+            .json()
+            .await?)
     }
 
     // Query Current Fee Rate
@@ -333,43 +485,41 @@ impl RestClient {
         &self,
         symbol: String,
     ) -> Result<ApiFinyResponse<Vec<FeeRate>>> {
-        if let Some(ref venue) = self.venue {
-            let req_url = format!("{}/asset/getCommissionRate", venue.rest);
-
-            let query = json!({
-                "accountId": self.conf.apifiny_account_id,
-                "venue": venue.name,
-                "symbol": symbol,
-            });
-
-            return Ok(self
-                .do_http(reqwest::Method::GET, req_url, Some(query), None)
-                .await?
-                .json()
-                .await?);
-        }
-        Err(super::Error::VenueNotSet())
+        let venue = &self.venue;
+        let req_url = format!("{}/asset/getCommissionRate", venue.rest);
+
+        let query = json!({
+            "accountId": self.client.conf.apifiny_account_id,
+            "venue": venue.name,
+            "symbol": symbol,
+        });
+
+        Ok(self
+            .client
+            .do_http(reqwest::Method::GET, req_url, Some(query), None)
+            .await?
+            .json()
+            .await?)
     }
 
     // Query Instant Transfer Quota
     // https://doc.apifiny.com/connect/#query-instant-transfer-quota
     pub async fn query_max_instant_amount(&self, currency: String) -> Result<ApiFinyResponse<f64>> {
-        if let Some(ref venue) = self.venue {
-            let req_url = format!("{}/asset/query-max-instant-amount", venue.rest);
-
-            let query = json!({
-                "accountId": self.conf.apifiny_account_id,
-                "venue": venue.name,
-                "currency": currency,
-            });
-
-            return Ok(self
-                .do_http(reqwest::Method::GET, req_url, Some(query), None)
-                .await?
-                .json()
-                .await?);
-        }
-        Err(super::Error::VenueNotSet())
+        let venue = &self.venue;
+        let req_url = format!("{}/asset/query-max-instant-amount", venue.rest);
+
+        let query = json!({
+            "accountId": self.client.conf.apifiny_account_id,
+            "venue": venue.name,
+            "currency": currency,
+        });
+
+        Ok(self
+            .client
+            .do_http(reqwest::Method::GET, req_url, Some(query), None)
+            .await?
+            .json()
+            .await?)
     }
 
     // Create Conversion
@@ -379,24 +529,23 @@ impl RestClient {
         &self,
         params: CreateConversionParams,
     ) -> Result<ApiFinyResponse<CreateConversionResponse>> {
-        if let Some(ref venue) = self.venue {
-            let req_url = format!("{}/asset/currencyConversion", venue.rest);
-
-            let mut body = json!({
-                "accountId": self.conf.apifiny_account_id,
-                "venue": venue.name, // venue name, support COINBASEPRO only
-            });
-
-            let params = serde_json::to_value(params)?;
-            super::utils::merge(&mut body, &params);
-
-            return Ok(self
-                .do_http(reqwest::Method::POST, req_url, None, Some(body))
-                .await?
-                .json()
-                .await?);
-        }
-        Err(super::Error::VenueNotSet())
+        let venue = &self.venue;
+        let req_url = format!("{}/asset/currencyConversion", venue.rest);
+
+        let mut body = json!({
+            "accountId": self.client.conf.apifiny_account_id,
+            "venue": venue.name, // venue name, support COINBASEPRO only
+        });
+
+        let params = serde_json::to_value(params)?;
+        super::utils::merge(&mut body, &params);
+
+        Ok(self
+            .client
+            .do_http(reqwest::Method::POST, req_url, None, Some(body))
+            .await?
+            .json()
+            .await?)
     }
 
     // Trading
@@ -404,129 +553,123 @@ impl RestClient {
     // Create New Order
     // https://doc.apifiny.com/connect/#create-new-order
     pub async fn create_order(&self, params: CreateOrderParams) -> Result<OrderResponse> {
-        if let Some(ref venue) = self.venue {
-            let req_url = format!("{}/order/newOrder", venue.rest);
-
-            let mut body = json!({
-                "accountId": self.conf.apifiny_account_id,
-                "venue": venue.name, // venue name, support COINBASEPRO only
-            });
-
-            let params = serde_json::to_value(params)?;
-            super::utils::merge(&mut body, &params);
-
-            return Ok(self
-                .do_http(reqwest::Method::POST, req_url, None, Some(body))
-                .await?
-                .json()
-                .await?);
-        }
-        Err(super::Error::VenueNotSet())
+        let venue = &self.venue;
+        let req_url = format!("{}/order/newOrder", venue.rest);
+
+        let mut body = json!({
+            "accountId": self.client.conf.apifiny_account_id,
+            "venue": venue.name, // venue name, support COINBASEPRO only
+        });
+
+        let params = serde_json::to_value(params)?;
+        super::utils::merge(&mut body, &params);
+
+        Ok(self
+            .client
+            .do_http(reqwest::Method::POST, req_url, None, Some(body))
+            .await?
+            .json()
+            .await?)
     }
 
     // Cancel Order
     // https://doc.apifiny.com/connect/#cancel-an-order
     // @param orderId	Order ID
     pub async fn cancel_order(&self, order_id: &str) -> Result<OrderResponse> {
-        if let Some(ref venue) = self.venue {
-            let req_url = format!("{}/order/cancelOrder", venue.rest);
-
-            let body = json!({
-                "accountId": self.conf.apifiny_account_id,
-                "venue": venue.name,
-                "orderId": order_id,
-            });
-
-            return Ok(self
-                .do_http(reqwest::Method::POST, req_url, None, Some(body))
-                .await?
-                .json()
-                .await?);
-        }
-        Err(super::Error::VenueNotSet())
+        let venue = &self.venue;
+        let req_url = format!("{}/order/cancelOrder", venue.rest);
+
+        let body = json!({
+            "accountId": self.client.conf.apifiny_account_id,
+            "venue": venue.name,
+            "orderId": order_id,
+        });
+
+        Ok(self
+            .client
+            .do_http(reqwest::Method::POST, req_url, None, Some(body))
+            .await?
+            .json()
+            .await?)
     }
 
     // Cancel All Order
     // https://doc.apifiny.com/connect/#cancel-an-order
     pub async fn cancel_account_venue_all_order(&self, symbol: &str) -> Result<Vec<String>> {
-        if let Some(ref venue) = self.venue {
-            let req_url = format!("{}/order/cancelAccountVenueAllOrder", venue.rest);
-
-            let body = json!({
-                "accountId": self.conf.apifiny_account_id,
-                "venue": venue.name,
-                "symbol": symbol,
-            });
-
-            return Ok(self
-                .do_http(reqwest::Method::POST, req_url, None, Some(body))
-                .await?
-                .json()
-                .await?);
-        }
-        Err(super::Error::VenueNotSet())
+        let venue = &self.venue;
+        let req_url = format!("{}/order/cancelAccountVenueAllOrder", venue.rest);
+
+        let body = json!({
+            "accountId": self.client.conf.apifiny_account_id,
+            "venue": venue.name,
+            "symbol": symbol,
+        });
+
+        Ok(self
+            .client
+            .do_http(reqwest::Method::POST, req_url, None, Some(body))
+            .await?
+            .json()
+            .await?)
     }
 
     // Get an Order
     // https://doc.apifiny.com/connect/#get-an-order
     pub async fn query_order_info(&self, order_id: &str) -> Result<OrderResponse> {
-        if let Some(ref venue) = self.venue {
-            let req_url = format!("{}/order/queryOrderInfo", venue.rest);
-
-            let query = json!({
-                "accountId": self.conf.apifiny_account_id,
-                "venue": venue.name,
-                "orderId": order_id,
-            });
-
-            return Ok(self
-                .do_http(reqwest::Method::GET, req_url, Some(query), None)
-                .await?
-                .json()
-                .await?);
-        }
-        Err(super::Error::VenueNotSet())
+        let venue = &self.venue;
+        let req_url = format!("{}/order/queryOrderInfo", venue.rest);
+
+        let query = json!({
+            "accountId": self.client.conf.apifiny_account_id,
+            "venue": venue.name,
+            "orderId": order_id,
+        });
+
+        Ok(self
+            .client
+            .do_http(reqwest::Method::GET, req_url, Some(query), None)
+            .await?
+            .json()
+            .await?)
     }
 
     // Query Multiple Orders by IDs
     // https://doc.apifiny.com/connect/#query-multiple-orders-by-ids
     pub async fn list_order_by_ids(&self, order_ids: Vec<String>) -> Result<Vec<OrderResponse>> {
-        if let Some(ref venue) = self.venue {
-            let req_url = format!("{}/order/listMultipleOrderInfo", venue.rest);
-
-            let ids = order_ids.join(",");
-            let query = json!({
-                "accountId": self.conf.apifiny_account_id,
-                "orderIdList": ids,
-            });
-
-            return Ok(self
-                .do_http(reqwest::Method::GET, req_url, Some(query), None)
-                .await?
-                .json()
-                .await?);
-        }
-        Err(super::Error::VenueNotSet())
+        let venue = &self.venue;
+        let req_url = format!("{}/order/listMultipleOrderInfo", venue.rest);
+
+        let ids = order_ids.join(",");
+        let query = json!({
+            "accountId": self.client.conf.apifiny_account_id,
+            "orderIdList": ids,
+        });
+
+        Ok(self
+            .client
+            .do_http(reqwest::Method::GET, req_url, Some(query), None)
+            .await?
+            .json()
+            .await?)
     }
 
     // Query All Open Orders
     // https://doc.apifiny.com/connect/#query-all-open-orders
     pub async fn list_open_order(&self) -> Result<Vec<OrderResponse>> {
-        if let Some(ref venue) = self.venue {
-            let req_url = format!("{}/order/listOpenOrder", venue.rest);
-
-            let query = json!({
-                "accountId": self.conf.apifiny_account_id,
-                "venue": venue.name,
-            });
-
-            return Ok(self
-                .do_http(reqwest::Method::GET, req_url, Some(query), None)
-                .await?
-                .json()
-                .await?);
-        }
-        Err(super::Error::VenueNotSet())
+        let venue = &self.venue;
+        let req_url = format!("{}/order/listOpenOrder", venue.rest);
+
+        let query = json!({
+            "accountId": self.client.conf.apifiny_account_id,
+            "venue": venue.name,
+        });
+
+        Ok(self
+            .client
+            .do_http(reqwest::Method::GET, req_url, Some(query), None)
+            .await?
+            .json()
+            .await?)
     }
 
     // Query All Completed Orders
@@ -535,23 +678,22 @@ impl RestClient {
         &self,
         params: ListCompletedOrderParams,
     ) -> Result<Vec<OrderResponse>> {
-        if let Some(ref venue) = self.venue {
-            let req_url = format!("{}/order/listCompletedOrder", venue.rest);
-
-            let mut query = json!({
-                "accountId": self.conf.apifiny_account_id,
-                "venue": venue.name,
-            });
-            let params = serde_json::to_value(&params)?;
-            super::utils::merge(&mut query, &params);
-
-            return Ok(self
-                .do_http(reqwest::Method::GET, req_url, Some(query), None)
-                .await?
-                .json()
-                .await?);
-        }
-        Err(super::Error::VenueNotSet())
+        let venue = &self.venue;
+        let req_url = format!("{}/order/listCompletedOrder", venue.rest);
+
+        let mut query = json!({
+            "accountId": self.client.conf.apifiny_account_id,
+            "venue": venue.name,
+        });
+        let params = serde_json::to_value(&params)?;
+        super::utils::merge(&mut query, &params);
+
+        Ok(self
+            .client
+            .do_http(reqwest::Method::GET, req_url, Some(query), None)
+            .await?
+            .json()
+            .await?)
     }
 
     // Query List Fills
@@ -560,65 +702,22 @@ impl RestClient {
         &self,
         params: ListFilledOrderParams,
     ) -> Result<Vec<OrderResponse>> {
-        if let Some(ref venue) = self.venue {
-            let req_url = format!("{}/order/listFilledOrder", venue.rest);
-
-            let mut query = json!({
-                "accountId": self.conf.apifiny_account_id,
-                "venue": venue.name,
-            });
-            let params = serde_json::to_value(&params)?;
-            super::utils::merge(&mut query, &params);
-
-            return Ok(self
-                .do_http(reqwest::Method::GET, req_url, Some(query), None)
-                .await?
-                .json()
-                .await?);
-        }
-        Err(super::Error::VenueNotSet())
-    }
-
-    // do real http request
-    async fn do_http(
-        &self,
-        method: reqwest::Method,
-        req_url: String,
-        query: Option<Value>,
-        body: Option<Value>,
-    ) -> Result<reqwest::Response> {
-        let client = get_http_client()?;
-
-        let mut req_builder = client
-            .get(&req_url)
-            .header(reqwest::header::CONTENT_TYPE, "application/json");
-
-        // set query params
-        if let Some(query) = query {
-            req_builder = req_builder.query(&query);
-        }
-
-        // set body params
-        if let Some(body) = body {
-            req_builder = req_builder.json(&body);
-        }
-
-        let mut req = req_builder.build()?;
-        signature_req(&self.conf, &mut req)?;
-
-        // change method
-        let m = req.method_mut();
-        *m = method;
+        let venue = &self.venue;
+        let req_url = format!("{}/order/listFilledOrder", venue.rest);
 
-        let resp = client.execute(req).await?;
+        let mut query = json!({
+            "accountId": self.client.conf.apifiny_account_id,
+            "venue": venue.name,
+        });
+        let params = serde_json::to_value(&params)?;
+        super::utils::merge(&mut query, &params);
 
-        let status_code = resp.status();
-        if !status_code.is_success() {
-            let msg = resp.text().await?;
-            return Err(http_error(req_url, status_code, msg));
-        }
-
-        Ok(resp)
+        Ok(self
+            .client
+            .do_http(reqwest::Method::GET, req_url, Some(query), None)
+            .await?
+            .json()
+            .await?)
     }
 }
 
@@ -631,7 +730,8 @@ pub struct CreateWithdrawParams {
     // a crypto address of the recipient
     pub address: String,
     // memo(Optional), It is usually necessary for EOS
-    pub memo: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
     // 	withdraw ticket
     pub ticket: String,
 }
@@ -647,7 +747,7 @@ pub struct TransferBetweenVenuesParams {
     pub target_venue: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct QueryAccountHistoryParams {
     #[serde(rename(serialize = "startTimeDate"))]
     pub start_time_date: DateTime<Utc>,
@@ -668,6 +768,16 @@ pub struct CreateConversionParams {
     amount: f64,
 }
 
+impl CreateConversionParams {
+    pub fn new(currency: &str, target_currency: &str, amount: f64) -> CreateConversionParams {
+        CreateConversionParams {
+            currency: currency.to_string(),
+            target_currency: target_currency.to_string(),
+            amount,
+        }
+    }
+}
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateOrderParams {
@@ -676,7 +786,24 @@ pub struct CreateOrderParams {
     order_info: OrderInfo,
 }
 
-#[derive(Debug, Serialize)]
+impl CreateOrderParams {
+    pub fn new(order_info: OrderInfo) -> CreateOrderParams {
+        CreateOrderParams {
+            order_id: None,
+            order_info,
+        }
+    }
+
+    pub fn order_id(mut self, order_id: &str) -> Self {
+        self.order_id = Some(order_id.to_string());
+        self
+    }
+}
+
+// max `limit` of `list_completed_order` and `list_filled_order`
+pub const MAX_ORDER_LIMIT: i32 = 1000;
+
+#[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ListCompletedOrderParams {
     // [Optional] Start Time
@@ -702,7 +829,31 @@ impl Default for ListCompletedOrderParams {
     }
 }
 
-#[derive(Debug, Serialize)]
+impl ListCompletedOrderParams {
+    pub fn start_time(mut self, start_time: DateTime<Utc>) -> Self {
+        self.start_time = Some(start_time);
+        self
+    }
+
+    pub fn end_time(mut self, end_time: DateTime<Utc>) -> Self {
+        self.end_time = Some(end_time);
+        self
+    }
+
+    // capped at `MAX_ORDER_LIMIT`
+    pub fn limit(mut self, limit: i32) -> Self {
+        self.limit = limit.min(MAX_ORDER_LIMIT);
+        self
+    }
+
+    // PART_FILLED, FILLED or CANCELLED
+    pub fn order_status(mut self, order_status: &str) -> Self {
+        self.order_status = Some(order_status.to_string());
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ListFilledOrderParams {
     // [Optional] Limit the list of fills to this symbol
@@ -731,11 +882,39 @@ impl Default for ListFilledOrderParams {
     }
 }
 
-#[derive(Debug, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct OrderInfo {
-    symbol: String,
-    // order type, LIMIT or MARKET(only some venues support) or STOP or SOR(smart order router)
+impl ListFilledOrderParams {
+    pub fn symbol(mut self, symbol: &str) -> Self {
+        self.symbol = Some(symbol.to_string());
+        self
+    }
+
+    pub fn order_id(mut self, order_id: &str) -> Self {
+        self.order_id = Some(order_id.to_string());
+        self
+    }
+
+    // capped at `MAX_ORDER_LIMIT`
+    pub fn limit(mut self, limit: i32) -> Self {
+        self.limit = limit.min(MAX_ORDER_LIMIT);
+        self
+    }
+
+    pub fn start_time(mut self, start_time: DateTime<Utc>) -> Self {
+        self.start_time = Some(start_time);
+        self
+    }
+
+    pub fn end_time(mut self, end_time: DateTime<Utc>) -> Self {
+        self.end_time = Some(end_time);
+        self
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderInfo {
+    symbol: String,
+    // order type, LIMIT or MARKET(only some venues support) or STOP or SOR(smart order router)
     order_type: String,
     // specifies how long the order remains in effect, 1=GTC, 3=IOC=, 7=PostOnly
     time_in_force: i32,
@@ -753,19 +932,91 @@ pub struct OrderInfo {
     stop_type: Option<String>,
 }
 
+impl OrderInfo {
+    // a GTC order, prices and quantities are sent as given
+    pub fn new(
+        symbol: &str,
+        order_type: &str,
+        order_side: &str,
+        limit_price: &str,
+        quantity: &str,
+    ) -> OrderInfo {
+        OrderInfo {
+            symbol: symbol.to_string(),
+            order_type: order_type.to_uppercase(),
+            time_in_force: 1,
+            order_side: order_side.to_uppercase(),
+            limit_price: limit_price.to_string(),
+            quantity: quantity.to_string(),
+            total: None,
+            trigger_price: None,
+            stop_type: None,
+        }
+    }
+
+    pub fn time_in_force(mut self, time_in_force: i32) -> Self {
+        self.time_in_force = time_in_force;
+        self
+    }
+
+    pub fn total(mut self, total: &str) -> Self {
+        self.total = Some(total.to_string());
+        self
+    }
+
+    // for STOP orders, `stop_type` is ENTRY or LOSS
+    pub fn trigger(mut self, trigger_price: &str, stop_type: &str) -> Self {
+        self.trigger_price = Some(trigger_price.to_string());
+        self.stop_type = Some(stop_type.to_uppercase());
+        self
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ApiFinyResponse<T> {
     result: Option<T>,
     error: Option<ApiFinyError>,
 }
 
+impl<T> ApiFinyResponse<T> {
+    pub fn result(&self) -> Option<&T> {
+        self.result.as_ref()
+    }
+
+    pub fn error(&self) -> Option<&ApiFinyError> {
+        self.error.as_ref()
+    }
+
+    // the result, or the api error reported in the response
+    pub fn into_result(self) -> Result<T> {
+        match (self.result, self.error) {
+            (_, Some(error)) => Err(super::Error::ApiError {
+                code: error.code,
+                message: error.message,
+            }),
+            (Some(result), None) => Ok(result),
+            (None, None) => Err(super::Error::EmptyResult()),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ApiFinyError {
     code: i64,
     message: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl ApiFinyError {
+    pub fn code(&self) -> i64 {
+        self.code
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct VenueInfo {
     exchange: String,
@@ -773,14 +1024,28 @@ pub struct VenueInfo {
     spt_instant: u8,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl VenueInfo {
+    pub fn exchange(&self) -> &str {
+        &self.exchange
+    }
+
+    pub fn status(&self) -> VenueStatus {
+        self.status
+    }
+
+    pub fn spt_instant(&self) -> u8 {
+        self.spt_instant
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
-enum VenueStatus {
+pub enum VenueStatus {
     Enabled,
     Disabled,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CurrencyInfo {
     currency: String,
@@ -793,14 +1058,48 @@ pub struct CurrencyInfo {
     coin: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl CurrencyInfo {
+    pub fn currency(&self) -> &str {
+        &self.currency
+    }
+
+    pub fn currency_precision(&self) -> i64 {
+        self.currency_precision
+    }
+
+    pub fn status(&self) -> CurrencyStatus {
+        self.status
+    }
+
+    pub fn withdraw_max_amount(&self) -> f64 {
+        self.withdraw_max_amount
+    }
+
+    pub fn withdraw_min_amount(&self) -> f64 {
+        self.withdraw_min_amount
+    }
+
+    pub fn withdraw_min_fee(&self) -> f64 {
+        self.withdraw_min_fee
+    }
+
+    pub fn instant_fee_rate(&self) -> &str {
+        &self.instant_fee_rate
+    }
+
+    pub fn coin(&self) -> &str {
+        &self.coin
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-enum CurrencyStatus {
+pub enum CurrencyStatus {
     DepositWithdraw,
     NotDepositNotWithdraw,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SymbolInfo {
     symbol: String,
@@ -819,7 +1118,65 @@ pub struct SymbolInfo {
     status: VenueStatus,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl SymbolInfo {
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    pub fn base_asset(&self) -> &str {
+        &self.base_asset
+    }
+
+    pub fn base_asset_precision(&self) -> i64 {
+        self.base_asset_precision
+    }
+
+    pub fn quote_asset(&self) -> &str {
+        &self.quote_asset
+    }
+
+    pub fn quote_precision(&self) -> i64 {
+        self.quote_precision
+    }
+
+    pub fn min_price(&self) -> Option<f64> {
+        self.min_price
+    }
+
+    pub fn max_price(&self) -> Option<f64> {
+        self.max_price
+    }
+
+    pub fn min_quantity(&self) -> f64 {
+        self.min_quantity
+    }
+
+    pub fn max_quantity(&self) -> f64 {
+        self.max_quantity
+    }
+
+    pub fn tick_size(&self) -> f64 {
+        self.tick_size
+    }
+
+    pub fn step_size(&self) -> f64 {
+        self.step_size
+    }
+
+    pub fn min_notional(&self) -> f64 {
+        self.min_notional
+    }
+
+    pub fn max_notional(&self) -> f64 {
+        self.max_notional
+    }
+
+    pub fn status(&self) -> VenueStatus {
+        self.status
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OrderBook {
     symbol: String,
@@ -829,10 +1186,42 @@ pub struct OrderBook {
     bids: Vec<PriceSizePair>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct PriceSizePair(i64, i64);
+impl OrderBook {
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
 
-#[derive(Debug, Serialize, Deserialize)]
+    pub fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    pub fn asks(&self) -> &[PriceSizePair] {
+        &self.asks
+    }
+
+    pub fn bids(&self) -> &[PriceSizePair] {
+        &self.bids
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriceSizePair(f64, f64);
+
+impl PriceSizePair {
+    pub fn new(price: f64, size: f64) -> PriceSizePair {
+        PriceSizePair(price, size)
+    }
+
+    pub fn price(&self) -> f64 {
+        self.0
+    }
+
+    pub fn size(&self) -> f64 {
+        self.1
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TradeOrder {
     symbol: String,
@@ -846,6 +1235,36 @@ pub struct TradeOrder {
     update_time: DateTime<Utc>,
 }
 
+impl TradeOrder {
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    pub fn provider(&self) -> &str {
+        &self.provider
+    }
+
+    pub fn price(&self) -> f64 {
+        self.price
+    }
+
+    pub fn side(&self) -> f64 {
+        self.side
+    }
+
+    pub fn trade_time(&self) -> DateTime<Utc> {
+        self.trade_time
+    }
+
+    pub fn exchange_id(&self) -> &str {
+        &self.exchange_id
+    }
+
+    pub fn update_time(&self) -> DateTime<Utc> {
+        self.update_time
+    }
+}
+
 #[derive(Debug)]
 pub enum Period {
     // 1m
@@ -886,7 +1305,7 @@ impl Display for Period {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct KLine {
     currency_pair: String,
@@ -902,7 +1321,49 @@ pub struct KLine {
     exchange: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl KLine {
+    pub fn currency_pair(&self) -> &str {
+        &self.currency_pair
+    }
+
+    pub fn period(&self) -> &str {
+        &self.period
+    }
+
+    pub fn open(&self) -> f64 {
+        self.open
+    }
+
+    pub fn high(&self) -> f64 {
+        self.high
+    }
+
+    pub fn low(&self) -> f64 {
+        self.low
+    }
+
+    pub fn close(&self) -> f64 {
+        self.close
+    }
+
+    pub fn vol(&self) -> f64 {
+        self.vol
+    }
+
+    pub fn count(&self) -> i64 {
+        self.count
+    }
+
+    pub fn timestamp(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+
+    pub fn exchange(&self) -> &str {
+        &self.exchange
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Ticker {
     symbol: String,
@@ -920,7 +1381,53 @@ pub struct Ticker {
     update_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl Ticker {
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    pub fn open(&self) -> f64 {
+        self.open
+    }
+
+    pub fn high(&self) -> f64 {
+        self.high
+    }
+
+    pub fn low(&self) -> f64 {
+        self.low
+    }
+
+    pub fn close(&self) -> f64 {
+        self.close
+    }
+
+    pub fn vol(&self) -> f64 {
+        self.vol
+    }
+
+    pub fn amount(&self) -> f64 {
+        self.amount
+    }
+
+    pub fn count(&self) -> i64 {
+        self.count
+    }
+
+    pub fn provider(&self) -> &str {
+        &self.provider
+    }
+
+    pub fn ticker_time(&self) -> DateTime<Utc> {
+        self.ticker_time
+    }
+
+    pub fn update_at(&self) -> DateTime<Utc> {
+        self.update_at
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ConsolidatedOrderBook {
     symbol: String,
@@ -930,14 +1437,24 @@ pub struct ConsolidatedOrderBook {
     update_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl ConsolidatedOrderBook {
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    pub fn update_at(&self) -> DateTime<Utc> {
+        self.update_at
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Order {
     price: f64,
     qty: f64,
     source: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Account {
     account_id: String,
@@ -950,7 +1467,33 @@ pub struct Account {
     sub_account_info: Vec<SubAccount>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl Account {
+    pub fn account_id(&self) -> &str {
+        &self.account_id
+    }
+
+    pub fn ex55_pin(&self) -> &str {
+        &self.ex55_pin
+    }
+
+    pub fn account_status(&self) -> &str {
+        &self.account_status
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    pub fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    pub fn sub_account_info(&self) -> &[SubAccount] {
+        &self.sub_account_info
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SubAccount {
     account_id: String,
@@ -962,7 +1505,29 @@ pub struct SubAccount {
     venue: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl SubAccount {
+    pub fn account_id(&self) -> &str {
+        &self.account_id
+    }
+
+    pub fn account_status(&self) -> &str {
+        &self.account_status
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    pub fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    pub fn venue(&self) -> &str {
+        &self.venue
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Balance {
     account_id: String,
@@ -970,7 +1535,33 @@ pub struct Balance {
     currency: String,
     amount: f64,
     available: f64,
-    frozen: i64,
+    frozen: f64,
+}
+
+impl Balance {
+    pub fn account_id(&self) -> &str {
+        &self.account_id
+    }
+
+    pub fn venue(&self) -> &str {
+        &self.venue
+    }
+
+    pub fn currency(&self) -> &str {
+        &self.currency
+    }
+
+    pub fn amount(&self) -> f64 {
+        self.amount
+    }
+
+    pub fn available(&self) -> f64 {
+        self.available
+    }
+
+    pub fn frozen(&self) -> f64 {
+        self.frozen
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -984,7 +1575,7 @@ pub struct DepositAddress {
     expired_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct WithdrawTicket {
     ticket: String,
@@ -992,7 +1583,17 @@ pub struct WithdrawTicket {
     expired_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl WithdrawTicket {
+    pub fn ticket(&self) -> &str {
+        &self.ticket
+    }
+
+    pub fn expired_at(&self) -> DateTime<Utc> {
+        self.expired_at
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Withdraw {
     account_id: String,
@@ -1017,7 +1618,73 @@ pub struct Withdraw {
     log_updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl Withdraw {
+    pub fn account_id(&self) -> &str {
+        &self.account_id
+    }
+
+    pub fn venue(&self) -> &str {
+        &self.venue
+    }
+
+    pub fn currency(&self) -> &str {
+        &self.currency
+    }
+
+    pub fn coin(&self) -> &str {
+        &self.coin
+    }
+
+    pub fn amount(&self) -> f64 {
+        self.amount
+    }
+
+    pub fn fee(&self) -> f64 {
+        self.fee
+    }
+
+    pub fn from_address(&self) -> &Value {
+        &self.from_address
+    }
+
+    pub fn target_address(&self) -> &str {
+        &self.target_address
+    }
+
+    pub fn kind(&self) -> &str {
+        &self.kind
+    }
+
+    pub fn action_type(&self) -> &str {
+        &self.action_type
+    }
+
+    pub fn action_note(&self) -> &str {
+        &self.action_note
+    }
+
+    pub fn log_id(&self) -> &str {
+        &self.log_id
+    }
+
+    pub fn tx_id(&self) -> &str {
+        &self.tx_id
+    }
+
+    pub fn status(&self) -> &str {
+        &self.status
+    }
+
+    pub fn log_created_at(&self) -> DateTime<Utc> {
+        self.log_created_at
+    }
+
+    pub fn log_updated_at(&self) -> DateTime<Utc> {
+        self.log_updated_at
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TransferBetweenVenuesResponse {
     account_id: String,
@@ -1040,6 +1707,48 @@ pub struct TransferBetweenVenuesResponse {
     log_updated_at: DateTime<Utc>,
 }
 
+impl TransferBetweenVenuesResponse {
+    pub fn account_id(&self) -> &str {
+        &self.account_id
+    }
+
+    pub fn venue(&self) -> &str {
+        &self.venue
+    }
+
+    pub fn currency(&self) -> &str {
+        &self.currency
+    }
+
+    pub fn amount(&self) -> f64 {
+        self.amount
+    }
+
+    pub fn fee(&self) -> f64 {
+        self.fee
+    }
+
+    pub fn target_venue(&self) -> &str {
+        &self.target_venue
+    }
+
+    pub fn log_id(&self) -> &str {
+        &self.log_id
+    }
+
+    pub fn status(&self) -> &str {
+        &self.status
+    }
+
+    pub fn log_created_at(&self) -> DateTime<Utc> {
+        self.log_created_at
+    }
+
+    pub fn log_updated_at(&self) -> DateTime<Utc> {
+        self.log_updated_at
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PagationResponse<T> {
@@ -1052,7 +1761,45 @@ pub struct PagationResponse<T> {
     pages: i32,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl<T> PagationResponse<T> {
+    pub fn records(&self) -> &[T] {
+        &self.records
+    }
+
+    pub fn into_records(self) -> Vec<T> {
+        self.records
+    }
+
+    // total number of records
+    pub fn total(&self) -> i32 {
+        self.total
+    }
+
+    // page size
+    pub fn size(&self) -> i32 {
+        self.size
+    }
+
+    // current page, starting at 1
+    pub fn current(&self) -> i32 {
+        self.current
+    }
+
+    pub fn hit_count(&self) -> bool {
+        self.hit_count
+    }
+
+    pub fn search_count(&self) -> bool {
+        self.search_count
+    }
+
+    // total number of pages
+    pub fn pages(&self) -> i32 {
+        self.pages
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AccountHistory {
     account_id: String,
@@ -1072,11 +1819,76 @@ pub struct AccountHistory {
     log_updated_at: DateTime<Utc>,
     action_type: String,
     action_note: String,
+    // either may be left out, e.g. for deposits
+    #[serde(default)]
     from_account_id: String,
+    #[serde(default)]
     to_account_id: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl AccountHistory {
+    pub fn account_id(&self) -> &str {
+        &self.account_id
+    }
+
+    pub fn currency(&self) -> &str {
+        &self.currency
+    }
+
+    pub fn status(&self) -> &str {
+        &self.status
+    }
+
+    pub fn kind(&self) -> &str {
+        &self.kind
+    }
+
+    pub fn amount(&self) -> f64 {
+        self.amount
+    }
+
+    pub fn fee(&self) -> f64 {
+        self.fee
+    }
+
+    pub fn target_address(&self) -> &str {
+        &self.target_address
+    }
+
+    pub fn coin(&self) -> &str {
+        &self.coin
+    }
+
+    pub fn log_id(&self) -> &str {
+        &self.log_id
+    }
+
+    pub fn log_created_at(&self) -> DateTime<Utc> {
+        self.log_created_at
+    }
+
+    pub fn log_updated_at(&self) -> DateTime<Utc> {
+        self.log_updated_at
+    }
+
+    pub fn action_type(&self) -> &str {
+        &self.action_type
+    }
+
+    pub fn action_note(&self) -> &str {
+        &self.action_note
+    }
+
+    pub fn from_account_id(&self) -> &str {
+        &self.from_account_id
+    }
+
+    pub fn to_account_id(&self) -> &str {
+        &self.to_account_id
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FeeRate {
     account_id: String,
@@ -1086,6 +1898,28 @@ pub struct FeeRate {
     special_rate: f64,
 }
 
+impl FeeRate {
+    pub fn account_id(&self) -> &str {
+        &self.account_id
+    }
+
+    pub fn trading_volume(&self) -> f64 {
+        self.trading_volume
+    }
+
+    pub fn take_fee(&self) -> f64 {
+        self.take_fee
+    }
+
+    pub fn make_fee(&self) -> f64 {
+        self.make_fee
+    }
+
+    pub fn special_rate(&self) -> f64 {
+        self.special_rate
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateConversionResponse {
@@ -1115,7 +1949,61 @@ pub struct CreateConversionResponse {
     id: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl CreateConversionResponse {
+    pub fn account_id(&self) -> &str {
+        &self.account_id
+    }
+
+    pub fn currency(&self) -> &str {
+        &self.currency
+    }
+
+    pub fn status(&self) -> &str {
+        &self.status
+    }
+
+    pub fn kind(&self) -> &str {
+        &self.kind
+    }
+
+    pub fn amount(&self) -> f64 {
+        self.amount
+    }
+
+    pub fn fee(&self) -> f64 {
+        self.fee
+    }
+
+    pub fn coin(&self) -> &str {
+        &self.coin
+    }
+
+    pub fn log_id(&self) -> &str {
+        &self.log_id
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    pub fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    pub fn action_type(&self) -> &str {
+        &self.action_type
+    }
+
+    pub fn action_note(&self) -> &str {
+        &self.action_note
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OrderResponse {
     // 	sub account ID
@@ -1163,6 +2051,88 @@ pub struct OrderResponse {
     total: Option<f64>,
 }
 
+impl OrderResponse {
+    pub fn account_id(&self) -> &str {
+        &self.account_id
+    }
+
+    pub fn venue(&self) -> &str {
+        &self.venue
+    }
+
+    pub fn order_id(&self) -> &str {
+        &self.order_id
+    }
+
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    pub fn order_type(&self) -> &str {
+        &self.order_type
+    }
+
+    pub fn order_side(&self) -> &str {
+        &self.order_side
+    }
+
+    pub fn trigger_price(&self) -> Option<f64> {
+        self.trigger_price
+    }
+
+    pub fn stop_type(&self) -> Option<&str> {
+        self.stop_type.as_deref()
+    }
+
+    pub fn trigger_time(&self) -> Option<DateTime<Utc>> {
+        self.trigger_time
+    }
+
+    pub fn limit_price(&self) -> Option<f64> {
+        self.limit_price
+    }
+
+    pub fn quantity(&self) -> f64 {
+        self.quantity
+    }
+
+    pub fn filled_average_price(&self) -> f64 {
+        self.filled_average_price
+    }
+
+    pub fn filled_cumulative_quantity(&self) -> f64 {
+        self.filled_cumulative_quantity
+    }
+
+    pub fn open_quantity(&self) -> f64 {
+        self.open_quantity
+    }
+
+    pub fn order_status(&self) -> &str {
+        &self.order_status
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    pub fn updated_at(&self) -> Option<DateTime<Utc>> {
+        self.updated_at
+    }
+
+    pub fn cancelled_updated_at(&self) -> Option<DateTime<Utc>> {
+        self.cancelled_updated_at
+    }
+
+    pub fn filled_updated_at(&self) -> Option<DateTime<Utc>> {
+        self.filled_updated_at
+    }
+
+    pub fn total(&self) -> Option<f64> {
+        self.total
+    }
+}
+
 // {
 //     "accountId": "STA-VENUE1_00000001",
 //     "venue": "VENUE1",
@@ -1185,3 +2155,34 @@ pub struct OrderResponse {
 // pub struct OpenOrder {
 
 // }
+
+// kline range as epoch milliseconds, only sent with both bounds
+fn kline_query(
+    start_time: Option<DateTime<Utc>>,
+    end_time: Option<DateTime<Utc>>,
+) -> Option<Value> {
+    match (start_time, end_time) {
+        (Some(start_time), Some(end_time)) => Some(json!({
+            "startTime": start_time.timestamp_millis(),
+            "endTime": end_time.timestamp_millis(),
+        })),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_kline_query() {
+        let start = Utc.timestamp_millis_opt(1584603012164).unwrap();
+        let end = Utc.timestamp_millis_opt(1584606612000).unwrap();
+        assert_eq!(
+            kline_query(Some(start), Some(end)),
+            Some(json!({"startTime": 1584603012164i64, "endTime": 1584606612000i64}))
+        );
+        assert_eq!(kline_query(Some(start), None), None);
+    }
+}
@@ -0,0 +1,200 @@
+use super::{
+    rest_client::{ConsolidatedOrderBook, OrderBook, Ticker, TradeOrder},
+    RestClient, Result,
+};
+use chrono::{DateTime, Utc};
+use futures::{stream, Future, Stream, StreamExt};
+use std::time::Duration;
+use tokio::time::{self, MissedTickBehavior};
+
+// Polling based market data streams, a fallback until push feeds exist.
+// Every poll goes through the client's rate limiter, snapshots whose update
+// time did not change are skipped, and dropping the stream stops polling.
+impl RestClient {
+    pub fn ticker_stream<'a>(
+        &'a self,
+        symbol: &'a str,
+        venue: &'a str,
+        interval: Duration,
+    ) -> impl Stream<Item = Result<Ticker>> + 'a {
+        poll_snapshots(
+            interval,
+            move || self.ticker(symbol, venue),
+            Ticker::update_at,
+        )
+    }
+
+    pub fn order_book_stream<'a>(
+        &'a self,
+        symbol: &'a str,
+        venue: &'a str,
+        interval: Duration,
+    ) -> impl Stream<Item = Result<OrderBook>> + 'a {
+        poll_snapshots(
+            interval,
+            move || self.order_book(symbol, venue),
+            OrderBook::updated_at,
+        )
+    }
+
+    pub fn consolidated_order_book_stream<'a>(
+        &'a self,
+        symbol: &'a str,
+        interval: Duration,
+    ) -> impl Stream<Item = Result<ConsolidatedOrderBook>> + 'a {
+        poll_snapshots(
+            interval,
+            move || self.consolidated_order_book(symbol),
+            ConsolidatedOrderBook::update_at,
+        )
+    }
+
+    // yields every trade once, in the order returned by the api
+    pub fn trade_stream<'a>(
+        &'a self,
+        symbol: &'a str,
+        venue: &'a str,
+        interval: Duration,
+    ) -> impl Stream<Item = Result<TradeOrder>> + 'a {
+        let polls = poll(interval, move || self.trade(symbol, venue));
+        let mut seen = TradeCursor::default();
+        polls.flat_map(move |trades| {
+            let items: Vec<Result<TradeOrder>> = match trades {
+                Ok(trades) => seen.advance(trades).into_iter().map(Ok).collect(),
+                Err(e) => vec![Err(e)],
+            };
+            stream::iter(items)
+        })
+    }
+}
+
+// Calls `fetch` once per interval, the first call happens immediately. A zero
+// interval is taken as a millisecond, the rate limiter paces the calls anyway.
+fn poll<'a, T, F, Fut>(interval: Duration, fetch: F) -> impl Stream<Item = Result<T>> + 'a
+where
+    T: 'a,
+    F: FnMut() -> Fut + 'a,
+    Fut: Future<Output = Result<T>> + 'a,
+{
+    // the timer is created on first poll, so streams can be built outside a runtime
+    stream::unfold((None, fetch), move |(ticker, mut fetch)| async move {
+        let mut ticker = ticker.unwrap_or_else(|| {
+            let mut ticker = time::interval(interval.max(Duration::from_millis(1)));
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            ticker
+        });
+        ticker.tick().await;
+        let item = fetch().await;
+        Some((item, (Some(ticker), fetch)))
+    })
+}
+
+// like `poll`, but drops snapshots with the same update time as the last one
+fn poll_snapshots<'a, T, F, Fut>(
+    interval: Duration,
+    fetch: F,
+    updated_at: fn(&T) -> DateTime<Utc>,
+) -> impl Stream<Item = Result<T>> + 'a
+where
+    T: 'a,
+    F: FnMut() -> Fut + 'a,
+    Fut: Future<Output = Result<T>> + 'a,
+{
+    let mut last = None;
+    poll(interval, fetch).filter(move |item| {
+        let fresh = match item {
+            Ok(snapshot) => {
+                let at = updated_at(snapshot);
+                let fresh = last != Some(at);
+                last = Some(at);
+                fresh
+            }
+            Err(_) => true,
+        };
+        futures::future::ready(fresh)
+    })
+}
+
+// remembers the newest trades seen so far
+#[derive(Default)]
+struct TradeCursor {
+    last: Option<DateTime<Utc>>,
+    // exchange ids of the trades at `last`
+    ids: Vec<String>,
+}
+
+impl TradeCursor {
+    fn advance(&mut self, trades: Vec<TradeOrder>) -> Vec<TradeOrder> {
+        let fresh: Vec<TradeOrder> = trades
+            .into_iter()
+            .filter(|t| match self.last {
+                Some(last) if t.trade_time() < last => false,
+                Some(last) if t.trade_time() == last => {
+                    !self.ids.iter().any(|id| id == t.exchange_id())
+                }
+                _ => true,
+            })
+            .collect();
+
+        if let Some(newest) = fresh.iter().map(|t| t.trade_time()).max() {
+            if self.last != Some(newest) {
+                self.ids.clear();
+                self.last = Some(newest);
+            }
+            self.ids.extend(
+                fresh
+                    .iter()
+                    .filter(|t| t.trade_time() == newest)
+                    .map(|t| t.exchange_id().to_string()),
+            );
+        }
+        fresh
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_poll_zero_interval() {
+        let mut calls = 0;
+        let polls = poll(Duration::ZERO, move || {
+            calls += 1;
+            futures::future::ready(Ok(calls))
+        });
+        let items: Vec<_> = polls.take(3).map(Result::unwrap).collect().await;
+        assert_eq!(items, vec![1, 2, 3]);
+    }
+
+    fn trade(id: &str, ms: i64) -> TradeOrder {
+        serde_json::from_value(json!({
+            "symbol": "BTCUSD",
+            "provider": "BINANCE",
+            "price": 1.0,
+            "side": 1.0,
+            "tradeTime": ms,
+            "exchangeId": id,
+            "updateTime": ms,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_trade_cursor() {
+        let mut cursor = TradeCursor::default();
+        let ids = |trades: Vec<TradeOrder>| -> Vec<String> {
+            trades.iter().map(|t| t.exchange_id().to_string()).collect()
+        };
+
+        let fresh = cursor.advance(vec![trade("1", 1), trade("2", 2)]);
+        assert_eq!(ids(fresh), vec!["1", "2"]);
+
+        let fresh = cursor.advance(vec![trade("1", 1), trade("2", 2), trade("3", 2)]);
+        assert_eq!(ids(fresh), vec!["3"]);
+
+        let fresh = cursor.advance(vec![trade("2", 2), trade("3", 2), trade("4", 3)]);
+        assert_eq!(ids(fresh), vec!["4"]);
+    }
+}
@@ -0,0 +1,237 @@
+use super::{
+    rest_client::{CurrencyInfo, SymbolInfo},
+    Error, RestClient, Result,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+// quote assets tried, longest first, when splitting a concatenated symbol
+// like `BTCUSDT` without a symbol table
+const KNOWN_QUOTES: &[&str] = &[
+    "USDT", "USDC", "BUSD", "TUSD", "USD", "EUR", "GBP", "BTC", "ETH", "BNB",
+];
+
+const SEPARATORS: [char; 3] = ['/', '-', '_'];
+
+// a trading pair, e.g. BTC/USD
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Symbol {
+    pub base: String,
+    pub quote: String,
+}
+
+impl Symbol {
+    pub fn new(base: &str, quote: &str) -> Symbol {
+        Symbol {
+            base: base.to_uppercase(),
+            quote: quote.to_uppercase(),
+        }
+    }
+
+    // `BTC/USD`, `BTC-USD`, ...
+    pub fn with_separator(&self, separator: &str) -> String {
+        format!("{}{}{}", self.base, separator, self.quote)
+    }
+
+    // `(base, quote)`, as taken by `RestClient::kline`
+    pub fn kline_parts(&self) -> (&str, &str) {
+        (&self.base, &self.quote)
+    }
+}
+
+// `BTCUSD`, as used by market data, order and fee endpoints
+impl Display for Symbol {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.base, self.quote)
+    }
+}
+
+// parses `BTC/USD`, `BTC-USD`, `BTC_USD` and `btcusd`, concatenated symbols
+// are split on a well known quote asset, use `SymbolTable::resolve` for others
+impl FromStr for Symbol {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Symbol> {
+        let s = s.trim();
+        if let Some((base, quote)) = s.split_once(SEPARATORS) {
+            if !base.is_empty() && !quote.is_empty() {
+                return Ok(Symbol::new(base, quote));
+            }
+            return Err(Error::InvalidSymbol(s.to_string()));
+        }
+
+        let upper = s.to_uppercase();
+        KNOWN_QUOTES
+            .iter()
+            .find(|q| upper.len() > q.len() && upper.ends_with(*q))
+            .map(|q| Symbol::new(&upper[..upper.len() - q.len()], q))
+            .ok_or_else(|| Error::InvalidSymbol(s.to_string()))
+    }
+}
+
+impl From<&SymbolInfo> for Symbol {
+    fn from(info: &SymbolInfo) -> Symbol {
+        Symbol::new(info.base_asset(), info.quote_asset())
+    }
+}
+
+// a currency on a specific chain, e.g. `USDT.ETH`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Coin {
+    pub currency: String,
+    // none for the currency's native chain, e.g. `BTC`
+    pub chain: Option<String>,
+}
+
+impl Coin {
+    pub fn new(currency: &str, chain: Option<&str>) -> Coin {
+        Coin {
+            currency: currency.to_uppercase(),
+            chain: chain.map(str::to_uppercase),
+        }
+    }
+}
+
+// `USDT.ETH`, as used by `query_address` and withdrawals
+impl Display for Coin {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self.chain {
+            Some(ref chain) => write!(f, "{}.{}", self.currency, chain),
+            None => write!(f, "{}", self.currency),
+        }
+    }
+}
+
+impl FromStr for Coin {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Coin> {
+        let s = s.trim();
+        match s.split_once('.') {
+            Some((currency, chain)) if !currency.is_empty() && !chain.is_empty() => {
+                Ok(Coin::new(currency, Some(chain)))
+            }
+            None if !s.is_empty() => Ok(Coin::new(s, None)),
+            _ => Err(Error::InvalidCoin(s.to_string())),
+        }
+    }
+}
+
+// symbols listed on a venue, from `list_symbol_info`
+#[derive(Debug, Clone, Default)]
+pub struct SymbolTable {
+    symbols: HashMap<String, SymbolInfo>,
+}
+
+impl SymbolTable {
+    pub fn new(symbols: Vec<SymbolInfo>) -> SymbolTable {
+        SymbolTable {
+            symbols: symbols
+                .into_iter()
+                .map(|info| (info.symbol().to_uppercase(), info))
+                .collect(),
+        }
+    }
+
+    // parse any supported format and check the symbol is listed
+    pub fn resolve(&self, s: &str) -> Result<Symbol> {
+        let key = s.trim().to_uppercase().replace(SEPARATORS, "");
+        self.symbols
+            .get(&key)
+            .map(Symbol::from)
+            .ok_or_else(|| Error::UnknownSymbol(s.to_string()))
+    }
+
+    pub fn info(&self, symbol: &Symbol) -> Option<&SymbolInfo> {
+        self.symbols.get(&symbol.to_string())
+    }
+
+    pub fn contains(&self, symbol: &Symbol) -> bool {
+        self.info(symbol).is_some()
+    }
+
+    pub fn symbols(&self) -> impl Iterator<Item = Symbol> + '_ {
+        self.symbols.values().map(Symbol::from)
+    }
+}
+
+// currencies and coins supported on a venue, from `list_currency`
+#[derive(Debug, Clone, Default)]
+pub struct CurrencyTable {
+    coins: HashMap<Coin, CurrencyInfo>,
+}
+
+impl CurrencyTable {
+    pub fn new(currencies: Vec<CurrencyInfo>) -> Result<CurrencyTable> {
+        let mut coins = HashMap::new();
+        for info in currencies {
+            coins.insert(info.coin().parse()?, info);
+        }
+        Ok(CurrencyTable { coins })
+    }
+
+    // parse `USDT.ETH` or `BTC` and check the coin is supported
+    pub fn resolve(&self, s: &str) -> Result<Coin> {
+        let coin: Coin = s.parse()?;
+        if self.coins.contains_key(&coin) {
+            Ok(coin)
+        } else {
+            Err(Error::UnknownCoin(s.to_string()))
+        }
+    }
+
+    pub fn info(&self, coin: &Coin) -> Option<&CurrencyInfo> {
+        self.coins.get(coin)
+    }
+
+    // every chain a currency can be moved on
+    pub fn coins_of(&self, currency: &str) -> Vec<&Coin> {
+        self.coins
+            .keys()
+            .filter(|c| c.currency.eq_ignore_ascii_case(currency))
+            .collect()
+    }
+}
+
+impl RestClient {
+    pub async fn symbol_table(&self, venue: &str) -> Result<SymbolTable> {
+        let symbols = self.list_symbol_info(venue).await?.into_result()?;
+        Ok(SymbolTable::new(symbols))
+    }
+
+    pub async fn currency_table(&self, venue: &str) -> Result<CurrencyTable> {
+        let currencies = self.list_currency(venue).await?.into_result()?;
+        CurrencyTable::new(currencies)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_symbol() {
+        let btc_usd = Symbol::new("BTC", "USD");
+        for s in ["BTC/USD", "btc-usd", "BTC_USD", "BTCUSD"] {
+            assert_eq!(s.parse::<Symbol>().unwrap(), btc_usd);
+        }
+        assert_eq!("ETHUSDT".parse::<Symbol>().unwrap().quote, "USDT");
+        assert_eq!(btc_usd.to_string(), "BTCUSD");
+        assert_eq!(btc_usd.with_separator("/"), "BTC/USD");
+        assert!("USD".parse::<Symbol>().is_err());
+        assert!("BTC/".parse::<Symbol>().is_err());
+    }
+
+    #[test]
+    fn test_parse_coin() {
+        let coin: Coin = "usdt.eth".parse().unwrap();
+        assert_eq!(coin, Coin::new("USDT", Some("ETH")));
+        assert_eq!(coin.to_string(), "USDT.ETH");
+        assert_eq!("BTC".parse::<Coin>().unwrap().to_string(), "BTC");
+        assert!("USDT.".parse::<Coin>().is_err());
+    }
+}
@@ -0,0 +1,232 @@
+use super::{
+    rest_client::{AccountHistory, QueryAccountHistoryParams, VenueClient},
+    Error, Result,
+};
+use chrono::{DateTime, Utc};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::{sync::Notify, time::Instant};
+
+// records requested per activity list page
+const PAGE_LIMIT: i32 = 100;
+
+// status of an account activity that will not change any more
+pub fn is_terminal_status(status: &str) -> bool {
+    status == "COMPLETED" || status == "CANCELLED"
+}
+
+// stops a running tracker from another task
+#[derive(Debug, Clone, Default)]
+pub struct CancelHandle {
+    inner: Arc<(AtomicBool, Notify)>,
+}
+
+impl CancelHandle {
+    pub fn cancel(&self) {
+        self.inner.0.store(true, Ordering::SeqCst);
+        self.inner.1.notify_waiters();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.0.load(Ordering::SeqCst)
+    }
+
+    async fn cancelled(&self) {
+        let notified = self.inner.1.notified();
+        if !self.is_cancelled() {
+            notified.await;
+        }
+    }
+}
+
+// the interval after `interval`, doubled up to `max`
+fn next_interval(interval: Duration, max: Duration) -> Duration {
+    interval.saturating_mul(2).min(max)
+}
+
+// wait `interval` at `now`, but not past the deadline
+fn next_wait(interval: Duration, deadline: Option<Instant>, now: Instant) -> Duration {
+    match deadline {
+        Some(d) => interval.min(d.saturating_duration_since(now)),
+        None => interval,
+    }
+}
+
+// a poll stops once every pending id is found or there are no more pages
+fn is_last_page(found: usize, pending: usize, page: i32, pages: i32) -> bool {
+    found >= pending || page >= pages
+}
+
+#[derive(Debug, Clone)]
+pub struct TrackerConfig {
+    // wait before the second poll, doubled after every poll
+    pub initial_interval: Duration,
+    pub max_interval: Duration,
+    // give up after this long, none waits forever
+    pub timeout: Option<Duration>,
+}
+
+impl Default for TrackerConfig {
+    fn default() -> Self {
+        TrackerConfig {
+            initial_interval: Duration::from_secs(2),
+            max_interval: Duration::from_secs(60),
+            timeout: Some(Duration::from_secs(60 * 60)),
+        }
+    }
+}
+
+// Follows `transfer_to_venue`, `create_conversion` and withdraw log ids
+// through the account activity list until they are COMPLETED or CANCELLED.
+pub struct ActivityTracker<'a> {
+    client: VenueClient<'a>,
+    config: TrackerConfig,
+    cancel: CancelHandle,
+}
+
+impl<'a> ActivityTracker<'a> {
+    pub fn new(client: VenueClient<'a>, config: TrackerConfig) -> ActivityTracker<'a> {
+        ActivityTracker {
+            client,
+            config,
+            cancel: CancelHandle::default(),
+        }
+    }
+
+    // a handle that stops `track` and `track_many`
+    pub fn cancel_handle(&self) -> CancelHandle {
+        self.cancel.clone()
+    }
+
+    // the final record of `log_id`, created at or after `since`
+    pub async fn track(&self, log_id: &str, since: DateTime<Utc>) -> Result<AccountHistory> {
+        let mut results = self.track_many(&[(log_id.to_string(), since)]).await;
+        results
+            .remove(log_id)
+            .unwrap_or_else(|| Err(Error::EmptyResult()))
+    }
+
+    // Track several log ids at once, every poll walks the activity list once
+    // for all of them. Ids still pending on timeout or cancel get an error.
+    pub async fn track_many(
+        &self,
+        log_ids: &[(String, DateTime<Utc>)],
+    ) -> HashMap<String, Result<AccountHistory>> {
+        let mut pending: HashMap<String, DateTime<Utc>> = log_ids.iter().cloned().collect();
+        let mut done = HashMap::new();
+        let deadline = self.config.timeout.map(|t| Instant::now() + t);
+        let mut interval = self.config.initial_interval;
+
+        while !pending.is_empty() {
+            match self.poll(&pending).await {
+                Ok(records) => {
+                    for record in records {
+                        if pending.remove(record.log_id()).is_some() {
+                            done.insert(record.log_id().to_string(), Ok(record));
+                        }
+                    }
+                }
+                Err(e) => tracing::warn!("poll account activity failed: {}", e),
+            }
+            if pending.is_empty() {
+                break;
+            }
+
+            let wait = tokio::time::sleep(next_wait(interval, deadline, Instant::now()));
+            let stop = tokio::select! {
+                _ = wait => deadline.map(|d| Instant::now() >= d).unwrap_or(false),
+                _ = self.cancel.cancelled() => true,
+            };
+            if stop || self.cancel.is_cancelled() {
+                let cancelled = self.cancel.is_cancelled();
+                for (log_id, _) in pending.drain() {
+                    let e = if cancelled {
+                        Error::Cancelled()
+                    } else {
+                        Error::Timeout(log_id.clone())
+                    };
+                    done.insert(log_id, Err(e));
+                }
+                break;
+            }
+            interval = next_interval(interval, self.config.max_interval);
+        }
+        done
+    }
+
+    // terminal records of any pending log id, walking every page
+    async fn poll(&self, pending: &HashMap<String, DateTime<Utc>>) -> Result<Vec<AccountHistory>> {
+        let since = match pending.values().min() {
+            Some(since) => *since,
+            None => return Ok(vec![]),
+        };
+
+        let mut found = vec![];
+        let mut page = 1;
+        loop {
+            let params = QueryAccountHistoryParams {
+                start_time_date: since - chrono::Duration::minutes(1),
+                end_time_date: self.client.client().clock().now(),
+                limit: PAGE_LIMIT,
+                page,
+            };
+            let resp = self
+                .client
+                .query_asset_activity_list(params)
+                .await?
+                .into_result()?;
+            let pages = resp.pages();
+            found.extend(
+                resp.into_records()
+                    .into_iter()
+                    .filter(|r| pending.contains_key(r.log_id()) && is_terminal_status(r.status())),
+            );
+            if is_last_page(found.len(), pending.len(), page, pages) {
+                return Ok(found);
+            }
+            page += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_terminal_status() {
+        assert!(is_terminal_status("COMPLETED"));
+        assert!(is_terminal_status("CANCELLED"));
+        assert!(!is_terminal_status("SUBMITTED"));
+        assert!(!is_terminal_status("completed"));
+    }
+
+    #[test]
+    fn test_schedule() {
+        let secs = Duration::from_secs;
+        assert_eq!(next_interval(secs(2), secs(60)), secs(4));
+        assert_eq!(next_interval(secs(40), secs(60)), secs(60));
+        assert_eq!(next_interval(Duration::MAX, secs(60)), secs(60));
+
+        let now = Instant::now();
+        assert_eq!(next_wait(secs(8), None, now), secs(8));
+        assert_eq!(next_wait(secs(8), Some(now + secs(3)), now), secs(3));
+        // past the deadline, check it right away
+        assert_eq!(next_wait(secs(8), Some(now), now + secs(1)), secs(0));
+    }
+
+    #[test]
+    fn test_is_last_page() {
+        assert!(!is_last_page(1, 2, 1, 3));
+        assert!(is_last_page(2, 2, 1, 3));
+        assert!(is_last_page(1, 2, 3, 3));
+        // an empty list reports no pages
+        assert!(is_last_page(0, 2, 1, 0));
+    }
+}
@@ -0,0 +1,316 @@
+use super::{
+    rest_client::VenueClient, utils::get_http_client, withdraw::WithdrawHandle, Coin, Error, Result,
+};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, path::Path, time::Duration};
+
+// default wait for an approval decision, a person may be deciding
+const APPROVAL_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+// an address withdrawals may be sent to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AllowlistEntry {
+    // currency and chain, e.g. `USDT.ETH`
+    pub coin: String,
+    pub address: String,
+    // required memo/tag, if the address needs one
+    #[serde(default)]
+    pub memo: Option<String>,
+    // free text, e.g. `cold wallet`
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+impl AllowlistEntry {
+    fn matches(&self, coin: &Coin, address: &str, memo: Option<&str>) -> bool {
+        self.coin.parse::<Coin>().ok().as_ref() == Some(coin)
+            && self.address == address
+            && self.memo.as_deref() == memo
+    }
+}
+
+// Allowlist config file, e.g.
+// {
+//   "approval_thresholds": { "USDT": 10000 },
+//   "entries": [{ "coin": "USDT.ETH", "address": "0x...", "label": "cold wallet" }]
+// }
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Allowlist {
+    // per currency amount above which a second approval is needed,
+    // currencies without a threshold always need one
+    #[serde(default)]
+    pub approval_thresholds: HashMap<String, f64>,
+    pub entries: Vec<AllowlistEntry>,
+}
+
+impl Allowlist {
+    pub fn load(path: impl AsRef<Path>) -> Result<Allowlist> {
+        let data = std::fs::read(path)?;
+        Ok(serde_json::from_slice(&data)?)
+    }
+
+    // the entry a withdrawal goes to, or an error if it is not listed
+    pub fn check(&self, coin: &Coin, address: &str, memo: Option<&str>) -> Result<&AllowlistEntry> {
+        self.entries
+            .iter()
+            .find(|e| e.matches(coin, address, memo))
+            .ok_or_else(|| Error::NotAllowlisted(format!("{} {}", coin, address)))
+    }
+
+    pub fn needs_approval(&self, coin: &Coin, amount: f64) -> bool {
+        match self.approval_thresholds.get(&coin.currency) {
+            Some(threshold) => amount > *threshold,
+            None => true,
+        }
+    }
+}
+
+// a withdrawal waiting for approval
+#[derive(Debug, Clone, Serialize)]
+pub struct WithdrawRequest {
+    // operator asking for the withdrawal, can't approve it as well
+    pub requester: String,
+    pub coin: String,
+    pub amount: f64,
+    pub address: String,
+    pub memo: Option<String>,
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "decision", rename_all = "lowercase")]
+pub enum Approval {
+    Approved { approver: String },
+    Rejected { reason: String },
+}
+
+// second sign-off for large withdrawals, e.g. another operator or a local service
+#[async_trait]
+pub trait ApprovalHook: Send + Sync {
+    async fn approve(&self, request: &WithdrawRequest) -> Result<Approval>;
+}
+
+// posts the request as json to a local approval service, which answers with
+// `{"decision": "approved", "approver": "..."}` or
+// `{"decision": "rejected", "reason": "..."}`
+pub struct HttpApprovalHook {
+    url: String,
+    client: reqwest::Client,
+    // no answer in time fails the withdrawal
+    timeout: Duration,
+}
+
+impl HttpApprovalHook {
+    // with the crate's http client, so `PROXY` applies as for api requests
+    pub fn new(url: &str) -> Result<HttpApprovalHook> {
+        Ok(HttpApprovalHook {
+            url: url.to_string(),
+            client: get_http_client()?,
+            timeout: APPROVAL_TIMEOUT,
+        })
+    }
+
+    // share a connection pool, e.g. the one passed to `RestClient::with_http_client`
+    pub fn with_http_client(mut self, client: reqwest::Client) -> HttpApprovalHook {
+        self.client = client;
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> HttpApprovalHook {
+        self.timeout = timeout;
+        self
+    }
+}
+
+#[async_trait]
+impl ApprovalHook for HttpApprovalHook {
+    async fn approve(&self, request: &WithdrawRequest) -> Result<Approval> {
+        let resp = self
+            .client
+            .post(&self.url)
+            .timeout(self.timeout)
+            .json(request)
+            .send()
+            .await?;
+        let status_code = resp.status();
+        if !status_code.is_success() {
+            let msg = resp.text().await?;
+            return Err(super::http_error(self.url.clone(), status_code, msg));
+        }
+        Ok(resp.json().await?)
+    }
+}
+
+// withdrawals restricted to an allowlist, with approval above a threshold
+pub struct Treasury<'a> {
+    client: VenueClient<'a>,
+    allowlist: Allowlist,
+    hook: Option<Box<dyn ApprovalHook>>,
+}
+
+impl<'a> Treasury<'a> {
+    pub fn new(client: VenueClient<'a>, allowlist: Allowlist) -> Treasury<'a> {
+        Treasury {
+            client,
+            allowlist,
+            hook: None,
+        }
+    }
+
+    pub fn with_approval_hook(mut self, hook: impl ApprovalHook + 'static) -> Treasury<'a> {
+        self.hook = Some(Box::new(hook));
+        self
+    }
+
+    pub fn allowlist(&self) -> &Allowlist {
+        &self.allowlist
+    }
+
+    // Refuses non listed addresses, asks the approval hook if needed and
+    // only then sends the withdrawal. An approval by `requester` themselves
+    // is refused, a withdrawal needs two people.
+    pub async fn withdraw(
+        &self,
+        requester: &str,
+        coin: &Coin,
+        amount: f64,
+        address: &str,
+        memo: Option<&str>,
+    ) -> Result<WithdrawHandle<'a>> {
+        let entry = self.allowlist.check(coin, address, memo)?;
+
+        if self.allowlist.needs_approval(coin, amount) {
+            let hook = self.hook.as_ref().ok_or(Error::ApprovalRequired())?;
+            let request = WithdrawRequest {
+                requester: requester.to_string(),
+                coin: coin.to_string(),
+                amount,
+                address: address.to_string(),
+                memo: memo.map(ToString::to_string),
+                label: entry.label.clone(),
+            };
+            match hook.approve(&request).await? {
+                Approval::Approved { approver } if !is_second_person(requester, &approver) => {
+                    return Err(Error::ApprovalRejected(format!(
+                        "approver {:?} is not a second person to {:?}",
+                        approver, requester
+                    )));
+                }
+                Approval::Approved { approver } => {
+                    tracing::info!(
+                        "withdraw of {} {} requested by {} approved by {}",
+                        amount,
+                        coin,
+                        requester,
+                        approver
+                    );
+                }
+                Approval::Rejected { reason } => return Err(Error::ApprovalRejected(reason)),
+            }
+        }
+
+        self.client.withdraw(coin, amount, address, memo).await
+    }
+}
+
+// approver and requester are both named and not the same operator
+fn is_second_person(requester: &str, approver: &str) -> bool {
+    let (requester, approver) = (requester.trim(), approver.trim());
+    !requester.is_empty() && !approver.is_empty() && !requester.eq_ignore_ascii_case(approver)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ApiFiny, RestClient, BINANCE};
+    use serde_json::json;
+
+    struct Approver(&'static str);
+
+    #[async_trait]
+    impl ApprovalHook for Approver {
+        async fn approve(&self, _: &WithdrawRequest) -> Result<Approval> {
+            Ok(Approval::Approved {
+                approver: self.0.to_string(),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_self_approval() {
+        let client = RestClient::new(ApiFiny::new("key-1", "secret", "STA-1")).unwrap();
+        let allowlist: Allowlist = serde_json::from_value(json!({
+            "entries": [{ "coin": "USDT.ETH", "address": "0xabc" }],
+        }))
+        .unwrap();
+        let treasury =
+            Treasury::new(client.venue(BINANCE), allowlist).with_approval_hook(Approver("Alice"));
+
+        let usdt = Coin::new("USDT", Some("ETH"));
+        for requester in ["alice", " Alice ", ""] {
+            assert!(matches!(
+                treasury
+                    .withdraw(requester, &usdt, 1.0, "0xabc", None)
+                    .await,
+                Err(Error::ApprovalRejected(_))
+            ));
+        }
+        assert!(is_second_person("bob", "alice"));
+    }
+
+    #[tokio::test]
+    async fn test_approval_timeout() {
+        // accepts the connection but never answers
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/approve", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (_stream, _) = listener.accept().await.unwrap();
+            std::future::pending::<()>().await
+        });
+
+        let hook = HttpApprovalHook::new(&url)
+            .unwrap()
+            .with_timeout(Duration::from_millis(50));
+        let request = WithdrawRequest {
+            requester: "bob".to_string(),
+            coin: "USDT.ETH".to_string(),
+            amount: 1.0,
+            address: "0xabc".to_string(),
+            memo: None,
+            label: None,
+        };
+        assert!(matches!(
+            hook.approve(&request).await,
+            Err(Error::ReqwestError(e)) if e.is_timeout()
+        ));
+        server.abort();
+    }
+
+    #[test]
+    fn test_allowlist() {
+        let allowlist: Allowlist = serde_json::from_value(json!({
+            "approval_thresholds": { "USDT": 1000.0 },
+            "entries": [
+                { "coin": "USDT.ETH", "address": "0xabc" },
+                { "coin": "EOS", "address": "exchange", "memo": "42" },
+            ],
+        }))
+        .unwrap();
+
+        let usdt = Coin::new("USDT", Some("ETH"));
+        assert!(allowlist.check(&usdt, "0xabc", None).is_ok());
+        assert!(allowlist.check(&usdt, "0xdef", None).is_err());
+        assert!(allowlist
+            .check(&Coin::new("USDT", Some("TRX")), "0xabc", None)
+            .is_err());
+
+        let eos = Coin::new("EOS", None);
+        assert!(allowlist.check(&eos, "exchange", Some("42")).is_ok());
+        assert!(allowlist.check(&eos, "exchange", None).is_err());
+
+        assert!(!allowlist.needs_approval(&usdt, 500.0));
+        assert!(allowlist.needs_approval(&usdt, 5000.0));
+        assert!(allowlist.needs_approval(&eos, 1.0));
+    }
+}
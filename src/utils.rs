@@ -3,68 +3,69 @@ use serde_json::Value;
 use tracing::debug;
 
 pub(crate) fn get_http_client() -> Result<reqwest::Client> {
+    http_client_with_proxy(std::env::var("PROXY").ok().as_deref())
+}
+
+pub(crate) fn http_client_with_proxy(proxy: Option<&str>) -> Result<reqwest::Client> {
     let mut client_builder = reqwest::Client::builder();
-    if let Ok(proxy) = std::env::var("PROXY") {
-        debug!("PROXY is {}", &proxy);
-        eprintln!("use proxy: {}", &proxy);
-        client_builder = client_builder.proxy(reqwest::Proxy::https(&proxy)?);
+    if let Some(proxy) = proxy {
+        debug!("PROXY is {}", redact_url(proxy));
+        client_builder = client_builder.proxy(reqwest::Proxy::https(proxy)?);
     }
     Ok(client_builder.build()?)
 }
 
-// signature apifiny token
-pub(crate) fn signature_req(conf: &crate::ApiFiny, req: &mut reqwest::Request) -> Result<()> {
-    use chrono::{Duration, Utc};
-    use reqwest::Method;
-    use serde_json::json;
-
-    let mut claims = std::collections::BTreeMap::new();
-    claims.insert("accountId", json!(conf.apifiny_account_id));
-    claims.insert("secretKeyId", json!(conf.apifiny_access_key));
-
-    // set JWT expiration 24 hours
-    let exp = Utc::now()
-        .checked_add_signed(Duration::hours(24))
-        .unwrap()
-        .timestamp();
-    claims.insert("exp", json!(exp));
-
-    // set rest api digest
-    use sha2::{Digest, Sha256};
-    let digest = match *req.method() {
-        Method::GET => req.url().query(),
-        Method::POST => {
-            if let Some(body) = req.body() {
-                let b = body.as_bytes().unwrap_or_default();
-                let s = std::str::from_utf8(b)?;
-                Some(s)
-            } else {
-                Some("")
+// `url` with user and password replaced, for logging
+pub(crate) fn redact_url(url: &str) -> String {
+    match reqwest::Url::parse(url) {
+        Ok(mut url) => {
+            if !url.username().is_empty() {
+                let _ = url.set_username("***");
+            }
+            if url.password().is_some() {
+                let _ = url.set_password(Some("***"));
             }
+            url.to_string()
         }
-        _ => None,
-    };
-
-    if let Some(digest) = digest {
-        let mut hasher = Sha256::new();
-        hasher.update(digest);
-        let digest = format!("{:x}", hasher.finalize());
-        claims.insert("digest", json!(digest));
+        // can't tell which part is secret
+        Err(_) => "***".to_string(),
     }
+}
 
-    let token = crate::token::signature(claims, conf.apifiny_secret_key.as_bytes());
-    req.headers_mut().append(
-        "signature",
-        reqwest::header::HeaderValue::from_str(token.as_str())?,
-    );
+// Set `query` as the url query with sorted keys and without nulls, the
+// signature covers exactly this string.
+pub(crate) fn set_query(url: &mut reqwest::Url, query: &Value) -> Result<()> {
+    let query = match query {
+        Value::Object(query) => query,
+        _ => return Err(crate::Error::Signing("query must be an object".to_string())),
+    };
+    let mut pairs: Vec<_> = query
+        .iter()
+        .filter_map(|(k, v)| {
+            let v = match v {
+                Value::Null => return None,
+                Value::String(s) => s.clone(),
+                v => v.to_string(),
+            };
+            Some((k, v))
+        })
+        .collect();
+    pairs.sort();
 
+    if pairs.is_empty() {
+        url.set_query(None);
+    } else {
+        url.query_pairs_mut()
+            .clear()
+            .extend_pairs(pairs.iter().map(|(k, v)| (k.as_str(), v.as_str())));
+    }
     Ok(())
 }
 
 // merge two Value
 pub fn merge(a: &mut Value, b: &Value) {
     match (a, b) {
-        (&mut Value::Object(ref mut a), &Value::Object(ref b)) => {
+        (Value::Object(a), Value::Object(b)) => {
             for (k, v) in b {
                 merge(a.entry(k.clone()).or_insert(Value::Null), v);
             }
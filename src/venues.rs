@@ -0,0 +1,264 @@
+use super::{
+    rest_client::{VenueInfo, VenueStatus},
+    Error, RestClient, Result, Venue, BINANCE, VENUES,
+};
+use std::{
+    collections::{BTreeMap, HashSet},
+    sync::{Arc, Mutex, OnceLock, RwLock},
+};
+
+// Apifiny hosts, `{code}` is replaced by a venue's host code.
+#[derive(Debug, Clone, PartialEq, Eq, clap::Args)]
+pub struct VenueUrls {
+    // private endpoints of one venue
+    #[clap(
+        long = "rest-url",
+        env = "APIFINY_REST_URL",
+        default_value = "https://api{code}.apifiny.com/ac/v2"
+    )]
+    pub rest: String,
+    #[clap(
+        long = "fix-url",
+        env = "APIFINY_FIX_URL",
+        default_value = "fixapi{code}.apifiny.com:1443"
+    )]
+    pub fix: String,
+    // public endpoints, shared by every venue
+    #[clap(
+        long = "base-url",
+        env = "APIFINY_BASE_URL",
+        default_value = "https://api.apifiny.com"
+    )]
+    pub base: String,
+}
+
+impl Default for VenueUrls {
+    fn default() -> Self {
+        VenueUrls {
+            rest: "https://api{code}.apifiny.com/ac/v2".to_string(),
+            fix: "fixapi{code}.apifiny.com:1443".to_string(),
+            base: "https://api.apifiny.com".to_string(),
+        }
+    }
+}
+
+impl VenueUrls {
+    pub fn venue(&self, name: &str, code: &str) -> Venue {
+        Venue {
+            name: intern(&name.to_uppercase()),
+            code: intern(code),
+            rest: intern(&self.rest.replace("{code}", code)),
+            fix: intern(&self.fix.replace("{code}", code)),
+        }
+    }
+}
+
+// Venues live as long as the program, like the built-in ones. Each distinct
+// string is leaked once, so refreshes do not grow memory.
+fn intern(s: &str) -> &'static str {
+    static STRINGS: OnceLock<Mutex<HashSet<&'static str>>> = OnceLock::new();
+
+    let mut strings = STRINGS.get_or_init(Default::default).lock().unwrap();
+    if let Some(s) = strings.get(s) {
+        return s;
+    }
+    let s: &'static str = Box::leak(s.to_string().into_boxed_str());
+    strings.insert(s);
+    s
+}
+
+// a venue with its state from `list_venue_info`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VenueEntry {
+    pub venue: Venue,
+    pub status: VenueStatus,
+    // supports instant transfers, assumed for built-in venues until refreshed
+    pub instant: bool,
+}
+
+// Venues known at runtime: the built-in ones, updated and extended from
+// `list_venue_info`. Clones share the venues.
+#[derive(Debug, Clone)]
+pub struct VenueRegistry {
+    urls: Arc<VenueUrls>,
+    // by upper case name
+    venues: Arc<RwLock<BTreeMap<String, VenueEntry>>>,
+}
+
+impl Default for VenueRegistry {
+    fn default() -> Self {
+        VenueRegistry::new(VenueUrls::default())
+    }
+}
+
+impl VenueRegistry {
+    // the built-in venues, at `urls`
+    pub fn new(urls: VenueUrls) -> VenueRegistry {
+        let venues = VENUES
+            .iter()
+            .map(|v| {
+                let entry = VenueEntry {
+                    venue: urls.venue(v.name, v.code),
+                    status: VenueStatus::Enabled,
+                    instant: true,
+                };
+                (v.name.to_string(), entry)
+            })
+            .collect();
+        VenueRegistry {
+            urls: Arc::new(urls),
+            venues: Arc::new(RwLock::new(venues)),
+        }
+    }
+
+    pub fn urls(&self) -> &VenueUrls {
+        &self.urls
+    }
+
+    // add or replace an enabled venue with its own host code
+    pub fn insert(&self, name: &str, code: &str) -> Venue {
+        let venue = self.urls.venue(name, code);
+        self.venues.write().unwrap().insert(
+            venue.name.to_string(),
+            VenueEntry {
+                venue,
+                status: VenueStatus::Enabled,
+                instant: false,
+            },
+        );
+        venue
+    }
+
+    // venue by name ignoring case, enabled or not
+    pub fn entry(&self, name: &str) -> Option<VenueEntry> {
+        self.venues
+            .read()
+            .unwrap()
+            .get(&name.to_uppercase())
+            .copied()
+    }
+
+    // enabled venue by name, ignoring case
+    pub fn get(&self, name: &str) -> Result<Venue> {
+        match self.entry(name) {
+            Some(e) if e.status == VenueStatus::Enabled => Ok(e.venue),
+            Some(_) => Err(Error::VenueDisabled(name.to_string())),
+            None => Err(Error::UnknownVenue(name.to_string())),
+        }
+    }
+
+    // The registered venue of the same name, so configured urls also apply
+    // to the built-in constants. Unknown venues are kept as they are.
+    pub fn resolve(&self, venue: Venue) -> Venue {
+        self.entry(venue.name).map(|e| e.venue).unwrap_or(venue)
+    }
+
+    pub fn entries(&self) -> Vec<VenueEntry> {
+        self.venues.read().unwrap().values().copied().collect()
+    }
+
+    pub fn enabled(&self) -> Vec<Venue> {
+        self.entries()
+            .into_iter()
+            .filter(|e| e.status == VenueStatus::Enabled)
+            .map(|e| e.venue)
+            .collect()
+    }
+
+    pub fn supports_instant(&self, name: &str) -> bool {
+        self.entry(name).is_some_and(|e| e.instant)
+    }
+
+    // Apply a `list_venue_info` listing. Listed venues take its status, new
+    // ones are added with their lower case name as host code, and venues it
+    // no longer lists are disabled.
+    pub fn update(&self, infos: &[VenueInfo]) {
+        let mut venues = self.venues.write().unwrap();
+        let listed: HashSet<String> = infos.iter().map(|i| i.exchange().to_uppercase()).collect();
+        for entry in venues.values_mut() {
+            if !listed.contains(entry.venue.name) {
+                entry.status = VenueStatus::Disabled;
+            }
+        }
+        for info in infos {
+            let name = info.exchange().to_uppercase();
+            let venue = match venues.get(&name) {
+                Some(e) => e.venue,
+                None => self.urls.venue(&name, &name.to_lowercase()),
+            };
+            venues.insert(
+                name,
+                VenueEntry {
+                    venue,
+                    status: info.status(),
+                    instant: info.spt_instant() == 1,
+                },
+            );
+        }
+    }
+}
+
+impl RestClient {
+    // update the client's venues from `list_venue_info`
+    pub async fn refresh_venues(&self) -> Result<()> {
+        let via = self
+            .venues()
+            .enabled()
+            .first()
+            .map_or(BINANCE.name, |v| v.name);
+        let infos = self.list_venue_info(via).await?.into_result()?;
+        self.venues().update(&infos);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_update() {
+        let registry = VenueRegistry::new(VenueUrls {
+            rest: "https://{code}.test/ac/v2".to_string(),
+            ..Default::default()
+        });
+        assert_eq!(
+            registry.get("binance").unwrap().rest,
+            "https://bn.test/ac/v2"
+        );
+        assert_eq!(registry.resolve(BINANCE).rest, "https://bn.test/ac/v2");
+
+        let infos: Vec<VenueInfo> = serde_json::from_value(json!([
+            {"exchange": "BINANCE", "status": "enabled", "sptInstant": 1},
+            {"exchange": "HUOBI", "status": "disabled", "sptInstant": 0},
+            {"exchange": "NEWEX", "status": "enabled", "sptInstant": 0},
+        ]))
+        .unwrap();
+        registry.update(&infos);
+
+        assert!(registry.supports_instant("Binance"));
+        assert!(matches!(
+            registry.get("huobi"),
+            Err(Error::VenueDisabled(_))
+        ));
+        assert!(matches!(
+            registry.get("kucoin"),
+            Err(Error::VenueDisabled(_))
+        ));
+        assert!(matches!(registry.get("nope"), Err(Error::UnknownVenue(_))));
+
+        let newex = registry.get("newex").unwrap();
+        assert_eq!(newex.name, "NEWEX");
+        assert_eq!(newex.rest, "https://newex.test/ac/v2");
+        assert!(!registry.supports_instant("NEWEX"));
+        assert_eq!(
+            registry
+                .enabled()
+                .iter()
+                .map(|v| v.name)
+                .collect::<Vec<_>>(),
+            vec!["BINANCE", "NEWEX"]
+        );
+    }
+}
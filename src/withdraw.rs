@@ -0,0 +1,147 @@
+use super::{
+    rest_client::{
+        AccountHistory, CreateWithdrawParams, CurrencyInfo, CurrencyStatus, VenueClient, Withdraw,
+    },
+    tracker::{ActivityTracker, TrackerConfig},
+    Coin, CurrencyTable, Error, Result,
+};
+
+// a submitted withdrawal
+pub struct WithdrawHandle<'a> {
+    client: VenueClient<'a>,
+    withdraw: Withdraw,
+}
+
+impl<'a> WithdrawHandle<'a> {
+    // the record returned when the withdrawal was submitted
+    pub fn withdraw(&self) -> &Withdraw {
+        &self.withdraw
+    }
+
+    pub fn log_id(&self) -> &str {
+        self.withdraw.log_id()
+    }
+
+    // resolves once the withdrawal is COMPLETED or CANCELLED
+    pub async fn wait(self, config: TrackerConfig) -> Result<AccountHistory> {
+        ActivityTracker::new(self.client, config)
+            .track(self.withdraw.log_id(), self.withdraw.log_created_at())
+            .await
+    }
+}
+
+impl<'a> VenueClient<'a> {
+    // Withdraw `amount` of `coin` to `address`: checks the amount against the
+    // currency limits, fetches a fresh withdraw ticket and submits the request.
+    // Any address is accepted, see `Treasury::withdraw` for the allowlisted flow.
+    pub async fn withdraw(
+        &self,
+        coin: &Coin,
+        amount: f64,
+        address: &str,
+        memo: Option<&str>,
+    ) -> Result<WithdrawHandle<'a>> {
+        let currencies = self.client().currency_table(self.venue().name).await?;
+        let info = currencies
+            .info(coin)
+            .ok_or_else(|| Error::UnknownCoin(coin.to_string()))?;
+        check_withdraw_amount(info, amount)?;
+
+        let ticket = self.create_withdraw_ticket().await?.into_result()?;
+        if ticket.expired_at() <= self.client().clock().now() {
+            return Err(Error::TicketExpired());
+        }
+
+        let params = CreateWithdrawParams {
+            coin: coin.to_string(),
+            amount,
+            address: address.to_string(),
+            memo: memo.map(ToString::to_string),
+            ticket: ticket.ticket().to_string(),
+        };
+        let withdraw = self
+            .create_withdraw(params)
+            .await?
+            .into_result()?
+            .into_iter()
+            .next()
+            .ok_or(Error::EmptyResult())?;
+
+        Ok(WithdrawHandle {
+            client: *self,
+            withdraw,
+        })
+    }
+}
+
+fn check_withdraw_amount(info: &CurrencyInfo, amount: f64) -> Result<()> {
+    if info.status() != CurrencyStatus::DepositWithdraw {
+        return Err(Error::InvalidWithdraw(format!(
+            "withdrawals of {} are disabled",
+            info.coin()
+        )));
+    }
+    if amount < info.withdraw_min_amount() {
+        return Err(Error::InvalidWithdraw(format!(
+            "amount {} is below the minimum {}",
+            amount,
+            info.withdraw_min_amount()
+        )));
+    }
+    if info.withdraw_max_amount() > 0.0 && amount > info.withdraw_max_amount() {
+        return Err(Error::InvalidWithdraw(format!(
+            "amount {} is above the maximum {}",
+            amount,
+            info.withdraw_max_amount()
+        )));
+    }
+    if amount <= info.withdraw_min_fee() {
+        return Err(Error::InvalidWithdraw(format!(
+            "amount {} does not cover the fee {}",
+            amount,
+            info.withdraw_min_fee()
+        )));
+    }
+    Ok(())
+}
+
+impl CurrencyTable {
+    // validate a withdrawal of `amount` without sending anything
+    pub fn check_withdraw(&self, coin: &Coin, amount: f64) -> Result<()> {
+        let info = self
+            .info(coin)
+            .ok_or_else(|| Error::UnknownCoin(coin.to_string()))?;
+        check_withdraw_amount(info, amount)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn usdt(status: &str) -> CurrencyInfo {
+        serde_json::from_value(json!({
+            "currency": "USDT",
+            "currencyPrecision": 6,
+            "status": status,
+            "withdrawMaxAmount": 1000.0,
+            "withdrawMinAmount": 10.0,
+            "withdrawMinFee": 1.0,
+            "instantFeeRate": "0",
+            "coin": "USDT.ETH",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_check_withdraw_amount() {
+        let info = usdt("DEPOSIT_WITHDRAW");
+        assert!(check_withdraw_amount(&info, 100.0).is_ok());
+        assert!(check_withdraw_amount(&info, 5.0).is_err());
+        assert!(check_withdraw_amount(&info, 5000.0).is_err());
+
+        let info = usdt("NOT_DEPOSIT_NOT_WITHDRAW");
+        assert!(check_withdraw_amount(&info, 100.0).is_err());
+    }
+}